@@ -0,0 +1,10 @@
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=FUNCTION_TIMER_DISABLED");
+    match std::env::var("FUNCTION_TIMER_DISABLED") {
+        Ok(value) if !value.is_empty() && value != "0" => {
+            println!("cargo:rustc-cfg=function_timer_disabled");
+        }
+        _ => {}
+    }
+}
@@ -1,22 +1,77 @@
 //! `time` macro. It can place on any function.
+use std::time::Duration;
+
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::quote;
 use syn::fold::Fold;
 use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::Impl;
 use syn::{
     parse_macro_input, Attribute, Block, Expr, ExprBlock, Ident, ImplItem, ImplItemFn, ItemFn,
-    ItemImpl, LitStr, Meta, Stmt, Type,
+    ItemImpl, Lit, LitBool, LitInt, LitStr, Meta, Stmt, Token, Type,
 };
 
 mod custom_keywords {
     syn::custom_keyword!(disable);
 }
 
+/// Placeholder of a metric name template, such as `{struct}`.
+#[derive(PartialEq)]
+enum Placeholder {
+    Struct,
+    Function,
+    Module,
+    Trait,
+}
+
+/// Part of a metric name template.
+enum Part {
+    Text(String),
+    Placeholder(Placeholder),
+}
+
+/// Parse a metric name template such as `"{struct}_{function}_seconds"`.
+fn parse_template(lit: &LitStr) -> syn::Result<Vec<Part>> {
+    let value = lit.value();
+    let mut parts = Vec::new();
+    let mut rest = value.as_str();
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            parts.push(Part::Text(rest[..start].to_string()));
+        }
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| syn::Error::new(lit.span(), "Unclosed placeholder"))?;
+        let placeholder = match &rest[start + 1..start + end] {
+            "struct" => Placeholder::Struct,
+            "function" => Placeholder::Function,
+            "module" => Placeholder::Module,
+            "trait" => Placeholder::Trait,
+            other => {
+                return Err(syn::Error::new(
+                    lit.span(),
+                    format!(
+                        "Unknown placeholder `{{{other}}}`, expected one of `{{struct}}`, `{{function}}`, `{{module}}`, `{{trait}}`"
+                    ),
+                ))
+            }
+        };
+        parts.push(Part::Placeholder(placeholder));
+        rest = &rest[start + end + 1..];
+    }
+    if !rest.is_empty() {
+        parts.push(Part::Text(rest.to_string()));
+    }
+
+    Ok(parts)
+}
+
 enum Name {
     Literal(LitStr),
+    Template(LitStr, Vec<Part>),
     Ident(Ident),
     Disable(custom_keywords::disable),
 }
@@ -29,15 +84,285 @@ impl Name {
     fn span(&self) -> Span {
         match self {
             Self::Literal(lit) => lit.span(),
+            Self::Template(lit, _) => lit.span(),
             Self::Ident(ident) => ident.span(),
             Self::Disable(tok) => tok.span(),
         }
     }
+
+    /// Check that template placeholders can be resolved.
+    ///
+    /// # Parameters
+    ///
+    /// * `is_impl` : whether `time` is on an impl block.
+    /// * `is_trait_impl` : whether `time` is on a trait impl block.
+    fn check_placeholders(&self, is_impl: bool, is_trait_impl: bool) -> syn::Result<()> {
+        if let Self::Template(lit, parts) = self {
+            for part in parts {
+                match part {
+                    Part::Placeholder(Placeholder::Struct) if !is_impl => {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            "`{struct}` can only be used when `time` is on an impl block",
+                        ))
+                    }
+                    Part::Placeholder(Placeholder::Trait) if !is_trait_impl => {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            "`{trait}` can only be used when `time` is on a trait impl block",
+                        ))
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse a duration such as `250ms`, `1.5s` or `100us`.
+fn parse_duration(lit: &LitStr) -> syn::Result<Duration> {
+    let value = lit.value();
+    let value = value.trim();
+    let index = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(index);
+    let error = || {
+        syn::Error::new(
+            lit.span(),
+            "Expected a duration with a unit among ns, us, ms, s, m, h (e.g. \"250ms\")",
+        )
+    };
+
+    let number: f64 = number.parse().map_err(|_| error())?;
+    let unit_in_nanos = match unit.trim() {
+        "ns" => 1.0,
+        "us" | "µs" => 1_000.0,
+        "ms" => 1_000_000.0,
+        "s" => 1_000_000_000.0,
+        "m" => 60_000_000_000.0,
+        "h" => 3_600_000_000_000.0,
+        _ => return Err(error()),
+    };
+
+    Ok(Duration::from_nanos((number * unit_in_nanos).round() as u64))
+}
+
+/// Parse a single duration literal or a list of them, such as `["100ms", "1s"]`.
+fn parse_durations(input: ParseStream) -> syn::Result<Vec<(Duration, String)>> {
+    let literals = if input.peek(syn::token::Bracket) {
+        let content;
+        syn::bracketed!(content in input);
+        Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?
+            .into_iter()
+            .collect()
+    } else {
+        vec![input.parse::<LitStr>()?]
+    };
+
+    literals
+        .iter()
+        .map(|lit| Ok((parse_duration(lit)?, lit.value().trim().to_string())))
+        .collect()
+}
+
+/// Which calls are timed.
+enum Sampling {
+    Rate(f64),
+    Every(u64),
+}
+
+/// Parse a sampling rate such as `0.01`.
+fn parse_rate(input: ParseStream) -> syn::Result<Sampling> {
+    let lit: Lit = input.parse()?;
+    let rate = match &lit {
+        Lit::Float(float) => float.base10_parse::<f64>()?,
+        Lit::Int(int) => int.base10_parse::<f64>()?,
+        _ => return Err(syn::Error::new(lit.span(), "Expected a sampling rate")),
+    };
+    if rate <= 0.0 || rate > 1.0 {
+        return Err(syn::Error::new(
+            lit.span(),
+            "Sampling rate must be greater than 0 and at most 1",
+        ));
+    }
+
+    Ok(Sampling::Rate(rate))
+}
+
+/// Parse the number of calls between two timed calls, such as `100`.
+fn parse_every(input: ParseStream) -> syn::Result<Sampling> {
+    let lit: LitInt = input.parse()?;
+    let every = lit.base10_parse::<u64>()?;
+    if every == 0 {
+        return Err(syn::Error::new(lit.span(), "Expected a positive number"));
+    }
+
+    Ok(Sampling::Every(every))
+}
+
+/// Parse the depth of the `caller` label, `true` being a depth of 1.
+fn parse_caller(input: ParseStream) -> syn::Result<Option<usize>> {
+    if input.peek(LitBool) {
+        let enabled = input.parse::<LitBool>()?.value;
+        return Ok(enabled.then_some(1));
+    }
+
+    let lit: LitInt = input.parse()?;
+    let depth = lit.base10_parse::<usize>()?;
+    if depth == 0 {
+        return Err(syn::Error::new(lit.span(), "Expected a positive number"));
+    }
+
+    Ok(Some(depth))
+}
+
+/// Options following the metric name, such as `slow = "250ms"`.
+#[derive(Default)]
+struct Options {
+    slow: Option<Duration>,
+    slow_counter: bool,
+    slo: Vec<(Duration, String)>,
+    sampling: Option<Sampling>,
+    count: bool,
+    cached: bool,
+    when: Option<Expr>,
+    namespace: Option<LitStr>,
+    struct_label: Option<LitStr>,
+    function_label: Option<LitStr>,
+    label_keys: Option<proc_macro2::TokenStream>,
+    clock: Option<proc_macro2::TokenStream>,
+    self_time: bool,
+    caller: Option<usize>,
+    outermost: bool,
+    recursion_depth: bool,
+    /// Keys of enabled options that need the timer on the thread's stack, so not async.
+    sync_only: Vec<Ident>,
+}
+
+impl Parse for Options {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut options = Options::default();
+        let mut slow_key = None;
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            let key_name = key.to_string();
+            if (key_name == "sample" || key_name == "every") && options.sampling.is_some() {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "Only one of `sample` and `every` can be used",
+                ));
+            }
+            match key_name.as_str() {
+                "slow" => {
+                    options.slow = Some(parse_duration(&input.parse()?)?);
+                    slow_key = Some(key);
+                }
+                "slow_counter" => options.slow_counter = input.parse::<LitBool>()?.value,
+                "slo" => options.slo.extend(parse_durations(input)?),
+                "sample" => options.sampling = Some(parse_rate(input)?),
+                "every" => options.sampling = Some(parse_every(input)?),
+                "count" => options.count = input.parse::<LitBool>()?.value,
+                "self_time" => {
+                    options.self_time = input.parse::<LitBool>()?.value;
+                    if options.self_time {
+                        options.sync_only.push(key);
+                    }
+                }
+                "cached" => options.cached = input.parse::<LitBool>()?.value,
+                "caller" => options.caller = parse_caller(input)?,
+                "recursion" => {
+                    let recursion: LitStr = input.parse()?;
+                    options.outermost = match recursion.value().as_str() {
+                        "all" => false,
+                        "outermost" => true,
+                        _ => {
+                            return Err(syn::Error::new(
+                                recursion.span(),
+                                "Expected `all` or `outermost`",
+                            ))
+                        }
+                    };
+                    if options.outermost {
+                        options.sync_only.push(key);
+                    }
+                }
+                "recursion_depth" => {
+                    options.recursion_depth = input.parse::<LitBool>()?.value;
+                    if options.recursion_depth {
+                        options.sync_only.push(key);
+                    }
+                }
+                "when" => options.when = Some(input.parse()?),
+                "namespace" => options.namespace = Some(input.parse()?),
+                "clock" => {
+                    let clock: LitStr = input.parse()?;
+                    let mode = match clock.value().as_str() {
+                        "wall" => quote!(function_timer::ClockMode::Wall),
+                        "thread_cpu" => quote!(function_timer::ClockMode::ThreadCpu),
+                        "both" => quote!(function_timer::ClockMode::Both),
+                        _ => {
+                            return Err(syn::Error::new(
+                                clock.span(),
+                                "Expected `wall`, `thread_cpu` or `both`",
+                            ))
+                        }
+                    };
+                    options.clock = Some(mode);
+                }
+                "struct_label" => options.struct_label = Some(input.parse()?),
+                "function_label" => options.function_label = Some(input.parse()?),
+                "label_keys" => {
+                    let preset: LitStr = input.parse()?;
+                    let keys = match preset.value().as_str() {
+                        "default" => quote!(function_timer::LabelKeys::DEFAULT),
+                        "opentelemetry" => quote!(function_timer::LabelKeys::OPEN_TELEMETRY),
+                        _ => {
+                            return Err(syn::Error::new(
+                                preset.span(),
+                                "Expected `default` or `opentelemetry`",
+                            ))
+                        }
+                    };
+                    options.label_keys = Some(keys);
+                }
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!("Unknown option `{key}`"),
+                    ))
+                }
+            }
+        }
+
+        // Slow calls would be silently ignored
+        let has_backend = cfg!(feature = "log") || cfg!(feature = "tracing");
+        if let (Some(key), false, false) = (slow_key, has_backend, options.slow_counter) {
+            return Err(syn::Error::new(
+                key.span(),
+                "`slow` requires the `log` or `tracing` feature, or `slow_counter = true`",
+            ));
+        }
+
+        Ok(options)
+    }
 }
 
 struct MetricName {
     struct_name: Option<String>,
+    trait_name: Option<String>,
     name: Name,
+    options: Options,
+    /// Errors found while folding functions.
+    errors: Vec<syn::Error>,
 }
 
 impl MetricName {
@@ -54,37 +379,304 @@ impl MetricName {
         })
     }
 
-    fn block_from(&self, block: Block, function_name: String) -> Block {
+    /// Find the body of the future built by `async-trait`.
+    ///
+    /// When `#[async_trait]` is expanded before `time`, the method body becomes
+    /// `Box::pin(async move { ... })`. Timing that block would only time the
+    /// creation of the future, so the inner async block is returned instead.
+    fn async_trait_body(block: &mut Block) -> Option<&mut Block> {
+        if block.stmts.len() != 1 {
+            return None;
+        }
+
+        if let Stmt::Expr(Expr::Call(call), None) = &mut block.stmts[0] {
+            let is_box_pin = if let Expr::Path(path) = &*call.func {
+                let segments: Vec<String> = path
+                    .path
+                    .segments
+                    .iter()
+                    .map(|s| s.ident.to_string())
+                    .collect();
+                segments.ends_with(&["Box".to_string(), "pin".to_string()])
+            } else {
+                false
+            };
+
+            if is_box_pin && call.args.len() == 1 {
+                if let Some(Expr::Async(async_block)) = call.args.first_mut() {
+                    if async_block.capture.is_some() {
+                        return Some(&mut async_block.block);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Resolve placeholders of a metric name template, after the `namespace`, if any.
+    ///
+    /// As `{module}` is resolved with `module_path!()`, the name is then built in constants, with
+    /// `::` replaced by `_`.
+    fn render_template(
+        &self,
+        lit: &LitStr,
+        parts: &[Part],
+        function_name: &str,
+        namespace: Option<&str>,
+    ) -> proc_macro2::TokenStream {
+        let resolve = |placeholder: &Placeholder| match placeholder {
+            Placeholder::Struct => self.struct_name.clone().unwrap_or_default(),
+            Placeholder::Function => function_name.to_string(),
+            Placeholder::Trait => self.trait_name.clone().unwrap_or_default(),
+            Placeholder::Module => unreachable!("module path is resolved by module_path!()"),
+        };
+
+        if parts
+            .iter()
+            .any(|part| matches!(part, Part::Placeholder(Placeholder::Module)))
+        {
+            let namespace = namespace.map(|namespace| quote!(#namespace,));
+            let parts = parts.iter().map(|part| match part {
+                Part::Text(text) => quote!(#text),
+                Part::Placeholder(Placeholder::Module) => quote!(module_path!()),
+                Part::Placeholder(placeholder) => {
+                    let value = resolve(placeholder);
+                    quote!(#value)
+                }
+            });
+            quote!({
+                const PARTS: &[&str] = &[#namespace #(#parts),*];
+                const LEN: usize = function_timer::template_len(PARTS);
+                const BYTES: [u8; LEN] = function_timer::template_bytes(PARTS);
+                const NAME: &str = function_timer::template_str(&BYTES);
+                NAME
+            })
+        } else {
+            let value: String = namespace
+                .into_iter()
+                .map(str::to_string)
+                .chain(parts.iter().map(|part| match part {
+                    Part::Text(text) => text.clone(),
+                    Part::Placeholder(placeholder) => resolve(placeholder),
+                }))
+                .collect();
+            let lit = LitStr::new(&value, lit.span());
+            quote!(#lit)
+        }
+    }
+
+    fn block_from(&mut self, mut block: Block, function_name: String, is_async: bool) -> Block {
+        // Time the execution of the future rather than its creation
+        if let Some(body) = Self::async_trait_body(&mut block) {
+            *body = self.block_from(body.clone(), function_name, true);
+            return block;
+        }
+
+        // Namespace is composed here so that timers don't allocate the name
+        let namespace = self
+            .options
+            .namespace
+            .as_ref()
+            .map(|namespace| format!("{}_", namespace.value()));
         let metric_name = match &self.name {
-            Name::Literal(lit) => quote!(#lit),
-            Name::Ident(ident) => quote!(#ident),
+            Name::Literal(lit) => match &namespace {
+                Some(namespace) => {
+                    let lit = LitStr::new(&format!("{namespace}{}", lit.value()), lit.span());
+                    quote!(#lit)
+                }
+                None => quote!(#lit),
+            },
+            Name::Template(lit, parts) => {
+                self.render_template(lit, parts, &function_name, namespace.as_deref())
+            }
+            Name::Ident(ident) => match &namespace {
+                Some(namespace) => quote!({
+                    const PARTS: &[&str] = &[#namespace, #ident];
+                    const LEN: usize = function_timer::template_len(PARTS);
+                    const BYTES: [u8; LEN] = function_timer::template_bytes(PARTS);
+                    const NAME: &str = function_timer::template_str(&BYTES);
+                    NAME
+                }),
+                None => quote!(#ident),
+            },
             // Early return the block as it shouldn't change (disable)
             Name::Disable(_) => return block,
         };
         let st = self.struct_name.clone();
 
-        let macro_stmt = if let Some(st) = st {
-            quote!(
-                let _guard = function_timer::FunctionTimer::new(#metric_name, Some(#st), #function_name);
-            )
+        let struct_name = if let Some(st) = &st {
+            quote!(Some(#st))
         } else {
-            quote!(
-                let _guard = function_timer::FunctionTimer::new(#metric_name, None, #function_name);
-            )
+            quote!(None)
         };
-        let mut statements: Vec<Stmt> = Vec::with_capacity(2);
-
-        let macro_stmt: Stmt = syn::parse2(macro_stmt).expect("Can't parse token");
-        statements.push(macro_stmt);
-
-        statements.push(Stmt::Expr(
-            Expr::Block(ExprBlock {
-                attrs: vec![],
-                label: None,
-                block,
-            }),
-            None,
-        ));
+        let mut timer = match &self.options.sampling {
+            None => quote!(
+                function_timer::FunctionTimer::new(#metric_name, #struct_name, #function_name)
+            ),
+            Some(sampling) => {
+                let sampling = match sampling {
+                    Sampling::Rate(rate) => quote!(function_timer::Sampling::Rate(#rate)),
+                    Sampling::Every(every) => quote!(function_timer::Sampling::Every(#every)),
+                };
+                quote!(
+                    function_timer::FunctionTimer::new_sampled(#metric_name, #struct_name, #function_name, {
+                        std::thread_local! {
+                            static CALLS: std::cell::Cell<u64> = std::cell::Cell::new(0);
+                        }
+                        #sampling.sample(&CALLS)
+                    })
+                )
+            }
+        };
+        if let Some(keys) = &self.options.label_keys {
+            timer = quote!(#timer.with_label_keys(#keys));
+        }
+        if let Some(struct_label) = &self.options.struct_label {
+            timer = quote!(#timer.with_struct_label(#struct_label));
+        }
+        if let Some(function_label) = &self.options.function_label {
+            timer = quote!(#timer.with_function_label(#function_label));
+        }
+        if let Some(mode) = &self.options.clock {
+            timer = quote!(#timer.with_clock_mode(#mode));
+        }
+        if let Some(depth) = self.options.caller {
+            timer = quote!(#timer.with_caller(#depth));
+        }
+        // Async functions can be suspended, so they can't be on the thread's stack of timers
+        if is_async {
+            // Reported once, even if the impl block has several async functions
+            if self.errors.is_empty() {
+                for key in &self.options.sync_only {
+                    self.errors.push(syn::Error::new(
+                        key.span(),
+                        format!("`{key}` can't be used on async functions"),
+                    ));
+                }
+            }
+        } else {
+            if self.options.outermost {
+                let depth = self.options.recursion_depth;
+                timer = quote!(#timer.with_outermost_only(#depth));
+            }
+            if self.options.self_time {
+                timer = quote!(#timer.with_self_time());
+            } else {
+                timer = quote!(#timer.with_call_stack());
+            }
+        }
+        if self.options.count {
+            timer = quote!(
+                #timer.with_call_counter({
+                    static COUNTER: std::sync::OnceLock<function_timer::metrics::Counter> =
+                        std::sync::OnceLock::new();
+                    &COUNTER
+                })
+            );
+        }
+        if self.options.cached {
+            timer = quote!(
+                #timer.with_cached_histogram({
+                    static HISTOGRAM: std::sync::OnceLock<function_timer::metrics::Histogram> =
+                        std::sync::OnceLock::new();
+                    &HISTOGRAM
+                })
+            );
+        }
+        if let Some(slow) = self.options.slow {
+            let nanos = slow.as_nanos() as u64;
+            let counter = self.options.slow_counter;
+            timer = quote!(
+                #timer.with_slow_threshold(std::time::Duration::from_nanos(#nanos), #counter)
+            );
+        }
+        if !self.options.slo.is_empty() {
+            let thresholds = self.options.slo.iter().map(|(duration, label)| {
+                let nanos = duration.as_nanos() as u64;
+                quote!((std::time::Duration::from_nanos(#nanos), #label))
+            });
+            timer = quote!(
+                #timer.with_slo({
+                    const SLO: &[(std::time::Duration, &str)] = &[#(#thresholds),*];
+                    SLO
+                })
+            );
+        }
+
+        if cfg!(feature = "tracing") {
+            let struct_field = st.map(|st| quote!("struct" = #st,));
+            timer = quote!(
+                #timer.with_span(function_timer::tracing::info_span!(
+                    #function_name,
+                    #struct_field
+                    "function" = #function_name,
+                    elapsed = function_timer::tracing::field::Empty
+                ))
+            );
+        }
+
+        let mut macro_stmts = Vec::with_capacity(2);
+        if let Some(when) = &self.options.when {
+            // Bound first, as struct literals aren't allowed in an `if` condition
+            macro_stmts.push(quote!(
+                let _guard = {
+                    let __when: bool = #when;
+                    if __when { Some(#timer) } else { None }
+                };
+            ));
+        } else {
+            macro_stmts.push(quote!(
+                let _guard = #timer;
+            ));
+        }
+        // Entered guard must not be held across await points
+        if cfg!(feature = "tracing") && !is_async {
+            if self.options.when.is_some() {
+                macro_stmts.push(quote!(
+                    let _span = _guard.as_ref().map(|guard| guard.span().enter());
+                ));
+            } else {
+                macro_stmts.push(quote!(
+                    let _span = _guard.span().enter();
+                ));
+            }
+        }
+        let mut statements: Vec<Stmt> = Vec::with_capacity(macro_stmts.len() + 1);
+
+        for macro_stmt in macro_stmts {
+            let macro_stmt: Stmt = syn::parse2(macro_stmt).expect("Can't parse token");
+            statements.push(macro_stmt);
+        }
+
+        if cfg!(feature = "tracing") && is_async {
+            // Enter the span each time the body is polled
+            let span = if self.options.when.is_some() {
+                quote!(_guard
+                    .as_ref()
+                    .map_or_else(function_timer::tracing::Span::none, |guard| guard
+                        .span()
+                        .clone()))
+            } else {
+                quote!(_guard.span().clone())
+            };
+            let body = quote!(
+                function_timer::tracing::Instrument::instrument(async move #block, #span).await
+            );
+            statements.push(Stmt::Expr(
+                syn::parse2(body).expect("Can't parse token"),
+                None,
+            ));
+        } else {
+            statements.push(Stmt::Expr(
+                Expr::Block(ExprBlock {
+                    attrs: vec![],
+                    label: None,
+                    block,
+                }),
+                None,
+            ));
+        }
 
         Block {
             brace_token: Default::default(),
@@ -100,16 +692,30 @@ impl Parse for MetricName {
             if lookahead.peek(custom_keywords::disable) {
                 Name::Disable(input.parse::<custom_keywords::disable>()?)
             } else if lookahead.peek(LitStr) {
-                Name::Literal(input.parse()?)
+                let lit: LitStr = input.parse()?;
+                let parts = parse_template(&lit)?;
+                if parts
+                    .iter()
+                    .any(|part| matches!(part, Part::Placeholder(_)))
+                {
+                    Name::Template(lit, parts)
+                } else {
+                    Name::Literal(lit)
+                }
             } else {
                 Name::Ident(input.parse().map_err(|error| {
                     syn::Error::new(error.span(), "Expected literal or identifier")
                 })?)
             };
 
+        let options = input.parse()?;
+
         Ok(Self {
             struct_name: None,
+            trait_name: None,
             name,
+            options,
+            errors: Vec::new(),
         })
     }
 }
@@ -125,7 +731,7 @@ impl Fold for MetricName {
         let mut result = i.clone();
         let block = i.block;
         let name = i.sig.ident.to_string();
-        let new_block = self.block_from(block, name);
+        let new_block = self.block_from(block, name, i.sig.asyncness.is_some());
         result.block = new_block;
 
         result
@@ -135,7 +741,7 @@ impl Fold for MetricName {
         let block = *i.block;
         let name = i.sig.ident.to_string();
 
-        let new_block = self.block_from(block, name);
+        let new_block = self.block_from(block, name, i.sig.asyncness.is_some());
 
         ItemFn {
             attrs: i.attrs,
@@ -150,6 +756,9 @@ impl Fold for MetricName {
         if let Type::Path(p) = *i.self_ty {
             self.struct_name = p.path.segments.last().map(|p| p.ident.to_string());
         }
+        if let Some((_, path, _)) = i.trait_ {
+            self.trait_name = path.segments.last().map(|p| p.ident.to_string());
+        }
         for item in i.items {
             if let ImplItem::Fn(method) = item {
                 new_items.push(ImplItem::Fn(self.fold_impl_item_fn(method)));
@@ -193,8 +802,42 @@ impl Parse for ImplOrFn {
 /// #[time("metric_name")]
 /// ```
 /// This macro can be on a function.
+///
+/// A literal metric name can contain placeholders, resolved when the macro is expanded :
+/// `{function}`, `{module}` (using `module_path!()`, with `::` replaced by `_`), and when on an
+/// impl block, `{struct}` and `{trait}`.
+///
+/// Options can follow the metric name :
+/// * `slow = "250ms"` : warn when the execution time is above the threshold. It requires the
+///   `log` or `tracing` feature, unless `slow_counter` is set.
+/// * `slow_counter = true` : also increment a `<metric_name>_slow_total` counter for slow calls.
+/// * `slo = "100ms"` or `slo = ["100ms", "1s"]` : increment a `<metric_name>_slo_violations_total`
+///   counter, with a `threshold` tag, for each threshold the execution time is above.
+/// * `sample = 0.01` : only time a random fraction of calls.
+/// * `every = 100` : only time one call out of 100 on each thread.
+/// * `count = true` : increment a `<metric_name>_calls_total` counter for every call, even
+///   those that aren't sampled. The counter handle is kept in a static, as with `cached`.
+/// * `self_time = true` : also emit a `<metric_name>_self_seconds` histogram, excluding time
+///   spent in nested timed functions. It can't be used on async functions.
+/// * `caller = true` or `caller = 2` : add a `caller` label with the enclosing timed functions,
+///   up to the given depth.
+/// * `recursion = "outermost"` : only record the outermost call of recursive calls
+///   (not on async functions). `recursion_depth = true` also records the recursion depth in
+///   a `<metric_name>_recursion_depth` histogram.
+/// * `cached = true` : keep the histogram handle in a static instead of looking it up in the
+///   recorder on every call.
+/// * `namespace = "db"` : the metric name becomes `db_<metric_name>`.
+/// * `clock = "thread_cpu"` : emit thread CPU time in a `<metric_name>_cpu` histogram instead of
+///   wall time (Linux only, wall time elsewhere). `clock = "both"` emits both.
+/// * `label_keys = "opentelemetry"` : use `code.namespace` and `code.function` tags instead of
+///   `struct` and `function` (`"default"` restores them).
+/// * `struct_label = "type"` and `function_label = "method"` : keys of the `struct` and
+///   `function` tags.
+/// * `when = <expr>` : only time calls for which the expression, that can use the function
+///   arguments, is `true`. It is evaluated once when entering the function.
 #[proc_macro_attribute]
 pub fn time(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let original = item.clone();
     let mut args = parse_macro_input!(attr as MetricName);
     let input = parse_macro_input!(item as ImplOrFn);
 
@@ -204,14 +847,32 @@ pub fn time(attr: TokenStream, item: TokenStream) -> TokenStream {
             .into();
     }
 
-    match input {
+    let is_trait_impl = matches!(&input, ImplOrFn::ImplStruct(item) if item.trait_.is_some());
+    if let Err(error) = args.name.check_placeholders(input.is_impl(), is_trait_impl) {
+        return error.into_compile_error().into();
+    }
+
+    // Attribute is still checked so that disabling timers doesn't hide errors
+    if cfg!(function_timer_disabled) {
+        return original;
+    }
+
+    let output = match input {
         ImplOrFn::Function(item_fn) => {
             let output = args.fold_item_fn(item_fn);
-            TokenStream::from(quote!(#output))
+            quote!(#output)
         }
         ImplOrFn::ImplStruct(impl_struct) => {
             let output = args.fold_item_impl(impl_struct);
-            TokenStream::from(quote!(#output))
+            quote!(#output)
         }
+    };
+
+    let mut errors = args.errors.into_iter();
+    if let Some(mut error) = errors.next() {
+        error.extend(errors);
+        return error.into_compile_error().into();
     }
+
+    TokenStream::from(output)
 }
@@ -0,0 +1,23 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use function_timer::time;
+use metrics_exporter_prometheus::PrometheusBuilder;
+
+#[time("bench_metric")]
+fn uncached() {}
+
+#[time("bench_metric", cached = true)]
+fn cached() {}
+
+fn bench_histogram(c: &mut Criterion) {
+    PrometheusBuilder::new()
+        .install_recorder()
+        .expect("Can't install recorder");
+
+    let mut group = c.benchmark_group("histogram");
+    group.bench_function("uncached", |b| b.iter(uncached));
+    group.bench_function("cached", |b| b.iter(cached));
+    group.finish();
+}
+
+criterion_group!(benches, bench_histogram);
+criterion_main!(benches);
@@ -0,0 +1,11 @@
+// Same as the macro build script, so that tests know whether timers are removed
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-env-changed=FUNCTION_TIMER_DISABLED");
+    match std::env::var("FUNCTION_TIMER_DISABLED") {
+        Ok(value) if !value.is_empty() && value != "0" => {
+            println!("cargo:rustc-cfg=function_timer_disabled");
+        }
+        _ => {}
+    }
+}
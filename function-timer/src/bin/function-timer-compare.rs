@@ -0,0 +1,66 @@
+//! Compare two reports written with `Report::write_json` or `Report::write_csv`.
+//!
+//! ```text
+//! function-timer-compare <baseline> <current> [--tolerance <fraction>]
+//! ```
+//!
+//! Files ending with `.csv` are read as CSV, others as JSON. Exits with `1` if a p50 or p99
+//! regressed by more than the tolerance (`0.1` by default), `2` on error.
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::process::ExitCode;
+
+use function_timer::report::Report;
+
+const USAGE: &str = "Usage: function-timer-compare <baseline> <current> [--tolerance <fraction>]";
+
+fn read_report(path: &str) -> Result<Report, String> {
+    let file = File::open(path).map_err(|e| format!("Unable to open {path}: {e}"))?;
+    let reader = BufReader::new(file);
+    let is_csv = Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("csv"));
+    let report = if is_csv {
+        Report::read_csv(reader)
+    } else {
+        Report::read_json(reader)
+    };
+    report.map_err(|e| format!("Unable to read {path}: {e}"))
+}
+
+fn run(args: &[String]) -> Result<bool, String> {
+    let mut paths = Vec::new();
+    let mut tolerance = 0.1;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--tolerance" {
+            let value = args.next().ok_or(USAGE)?;
+            tolerance = value
+                .parse()
+                .map_err(|e| format!("Invalid tolerance `{value}`: {e}"))?;
+        } else {
+            paths.push(arg);
+        }
+    }
+    let [baseline, current] = paths[..] else {
+        return Err(USAGE.to_string());
+    };
+
+    let comparison =
+        function_timer::compare(&read_report(baseline)?, &read_report(current)?, tolerance);
+    print!("{comparison}");
+    Ok(comparison.is_ok())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::from(2)
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Limit of the number of label sets per metric.
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+
+use metrics::{counter, Label};
+
+/// Value replacing tag values once the limit is reached.
+pub const OVERFLOW_VALUE: &str = "__other__";
+/// Counter incremented each time tag values are replaced.
+pub const OVERFLOW_COUNTER: &str = "function_timer_cardinality_overflow_total";
+
+/// Maximum number of label sets per metric, `0` if unlimited.
+static LIMIT: AtomicUsize = AtomicUsize::new(0);
+/// Label sets seen per metric. It is read on every call, but only written for new label sets.
+static SEEN: RwLock<BTreeMap<String, BTreeSet<Vec<Label>>>> = RwLock::new(BTreeMap::new());
+
+/// Limit the number of distinct label sets per metric name.
+///
+/// Once a metric has `limit` label sets, new ones have all their values replaced by
+/// [OVERFLOW_VALUE] and the [OVERFLOW_COUNTER] counter, with a `metric` tag, is incremented.
+/// `None` removes the limit. Label sets seen so far are forgotten.
+pub fn set_cardinality_limit(limit: Option<usize>) {
+    let mut seen = SEEN
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    seen.clear();
+    LIMIT.store(limit.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// Maximum number of distinct label sets per metric name.
+pub fn cardinality_limit() -> Option<usize> {
+    match LIMIT.load(Ordering::Relaxed) {
+        0 => None,
+        limit => Some(limit),
+    }
+}
+
+/// Whether label sets are limited.
+pub(crate) fn is_limited() -> bool {
+    LIMIT.load(Ordering::Relaxed) != 0
+}
+
+/// Return `labels`, or labels with [OVERFLOW_VALUE] values if the metric has too many
+/// label sets.
+pub(crate) fn guard(metric_name: &str, labels: Vec<Label>) -> Vec<Label> {
+    let limit = match cardinality_limit() {
+        Some(limit) => limit,
+        None => return labels,
+    };
+
+    let full = {
+        let seen = SEEN.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        match seen.get(metric_name) {
+            Some(label_sets) if label_sets.contains(&labels) => return labels,
+            Some(label_sets) => label_sets.len() >= limit,
+            None => false,
+        }
+    };
+    if !full {
+        let mut seen = SEEN
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        // Label sets may have been added in the meantime
+        let label_sets = seen.entry(metric_name.to_string()).or_default();
+        if label_sets.contains(&labels) {
+            return labels;
+        }
+        if label_sets.len() < limit {
+            label_sets.insert(labels.clone());
+            return labels;
+        }
+    }
+
+    counter!(OVERFLOW_COUNTER, "metric" => metric_name.to_string()).increment(1);
+    labels
+        .into_iter()
+        .map(|label| Label::new(label.key().to_string(), OVERFLOW_VALUE))
+        .collect()
+}
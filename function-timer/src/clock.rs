@@ -0,0 +1,89 @@
+//! Clocks used to measure wall time.
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+/// Source of time for timers.
+pub trait Clock: Send + Sync {
+    /// Current time, as a duration since an arbitrary but fixed origin.
+    fn now(&self) -> Duration;
+}
+
+/// Clock based on [Instant].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct InstantClock;
+
+impl Clock for InstantClock {
+    fn now(&self) -> Duration {
+        static ORIGIN: OnceLock<Instant> = OnceLock::new();
+        ORIGIN.get_or_init(Instant::now).elapsed()
+    }
+}
+
+/// Clock based on [quanta](https://crates.io/crates/quanta), which is usually faster to read
+/// than [Instant].
+#[cfg(feature = "quanta")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct QuantaClock;
+
+#[cfg(feature = "quanta")]
+impl Clock for QuantaClock {
+    fn now(&self) -> Duration {
+        static ORIGIN: OnceLock<quanta::Instant> = OnceLock::new();
+        let origin = *ORIGIN.get_or_init(quanta::Instant::now);
+        quanta::Instant::now().saturating_duration_since(origin)
+    }
+}
+
+#[cfg(not(feature = "quanta"))]
+static DEFAULT_CLOCK: InstantClock = InstantClock;
+#[cfg(feature = "quanta")]
+static DEFAULT_CLOCK: QuantaClock = QuantaClock;
+
+static CLOCK: OnceLock<&'static dyn Clock> = OnceLock::new();
+
+#[cfg(feature = "test-util")]
+thread_local! {
+    /// Clock overriding the global one on the current thread.
+    static LOCAL_CLOCK: std::cell::Cell<Option<&'static dyn Clock>> = const { std::cell::Cell::new(None) };
+}
+
+/// Error returned by [set_clock] when a clock has already been set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SetClockError;
+
+impl Display for SetClockError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "a clock has already been set")
+    }
+}
+
+impl Error for SetClockError {}
+
+/// Set the clock used by all timers. It can only be set once, preferably before any timed
+/// call. Timers started before keep the default clock until they end.
+///
+/// By default, it is [InstantClock], or `QuantaClock` with the `quanta` feature.
+pub fn set_clock(clock: &'static dyn Clock) -> Result<(), SetClockError> {
+    CLOCK.set(clock).map_err(|_| SetClockError)
+}
+
+/// Override the global clock on the current thread, returning the previous override.
+#[cfg(feature = "test-util")]
+pub(crate) fn set_local_clock(clock: Option<&'static dyn Clock>) -> Option<&'static dyn Clock> {
+    LOCAL_CLOCK.with(|local| local.replace(clock))
+}
+
+/// Clock in use.
+pub(crate) fn current() -> &'static dyn Clock {
+    #[cfg(feature = "test-util")]
+    if let Some(clock) = LOCAL_CLOCK.with(|local| local.get()) {
+        return clock;
+    }
+
+    match CLOCK.get() {
+        Some(clock) => *clock,
+        None => &DEFAULT_CLOCK,
+    }
+}
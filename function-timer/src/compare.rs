@@ -0,0 +1,135 @@
+//! Comparison of two reports, to detect latency regressions.
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use crate::report::{Report, Row};
+
+/// Percentile regressed beyond the tolerance.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Regression {
+    /// Metric name.
+    pub name: String,
+    /// Struct name, for methods.
+    pub struct_name: Option<String>,
+    /// Function name.
+    pub function: Option<String>,
+    /// Labels other than struct and function, as `(key, value)`.
+    pub labels: Vec<(String, String)>,
+    /// `p50` or `p99`.
+    pub percentile: &'static str,
+    /// Value in the baseline report.
+    pub baseline: Duration,
+    /// Value in the current report.
+    pub current: Duration,
+}
+
+impl Display for Regression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let labels: Vec<String> = [("struct", &self.struct_name), ("function", &self.function)]
+            .into_iter()
+            .filter_map(|(key, value)| Some(format!("{key}={}", value.as_ref()?)))
+            .chain(
+                self.labels
+                    .iter()
+                    .map(|(key, value)| format!("{key}={value}")),
+            )
+            .collect();
+        let ratio = self.current.as_secs_f64() / self.baseline.as_secs_f64();
+        write!(
+            f,
+            "{}{{{}}} {} : {:.2?} -> {:.2?} ({:+.1}%)",
+            self.name,
+            labels.join(","),
+            self.percentile,
+            self.baseline,
+            self.current,
+            (ratio - 1.0) * 100.0
+        )
+    }
+}
+
+/// Result of [compare].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Comparison {
+    regressions: Vec<Regression>,
+}
+
+impl Comparison {
+    /// Regressed percentiles, in order of the current report.
+    pub fn regressions(&self) -> &[Regression] {
+        &self.regressions
+    }
+
+    /// Whether there is no regression.
+    pub fn is_ok(&self) -> bool {
+        self.regressions.is_empty()
+    }
+}
+
+impl Display for Comparison {
+    /// Print one line per regression.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for regression in &self.regressions {
+            writeln!(f, "{regression}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Metric name, struct, function and sorted labels.
+type RowKey<'a> = (
+    &'a str,
+    Option<&'a str>,
+    Option<&'a str>,
+    Vec<&'a (String, String)>,
+);
+
+/// Row identity, regardless of label order.
+fn row_key(row: &Row) -> RowKey<'_> {
+    let mut labels: Vec<_> = row.labels.iter().collect();
+    labels.sort();
+    (
+        &row.name,
+        row.struct_name.as_deref(),
+        row.function.as_deref(),
+        labels,
+    )
+}
+
+/// List p50 and p99 of `current` exceeding those of `baseline` by more than `tolerance`, as
+/// a fraction (`0.1` allows 10% more).
+///
+/// Rows are matched by metric name, struct, function and labels. Rows missing from either
+/// report are ignored.
+pub fn compare(baseline: &Report, current: &Report, tolerance: f64) -> Comparison {
+    let baseline: BTreeMap<_, &Row> = baseline
+        .rows()
+        .iter()
+        .map(|row| (row_key(row), row))
+        .collect();
+
+    let mut regressions = Vec::new();
+    for row in current.rows() {
+        let before = match baseline.get(&row_key(row)) {
+            Some(before) => before,
+            None => continue,
+        };
+        for (percentile, before, after) in
+            [("p50", before.p50, row.p50), ("p99", before.p99, row.p99)]
+        {
+            if after.as_secs_f64() > before.as_secs_f64() * (1.0 + tolerance) {
+                regressions.push(Regression {
+                    name: row.name.clone(),
+                    struct_name: row.struct_name.clone(),
+                    function: row.function.clone(),
+                    labels: row.labels.clone(),
+                    percentile,
+                    baseline: before,
+                    current: after,
+                });
+            }
+        }
+    }
+    Comparison { regressions }
+}
@@ -0,0 +1,50 @@
+//! Thread CPU time.
+use std::time::Duration;
+
+/// Which time is measured by a timer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ClockMode {
+    /// Wall time, emitted in the `<metric_name>` histogram.
+    #[default]
+    Wall,
+    /// CPU time of the current thread, emitted in the `<metric_name>_cpu` histogram. Falls back
+    /// to wall time where it isn't available.
+    ThreadCpu,
+    /// Both wall time and thread CPU time.
+    Both,
+}
+
+impl ClockMode {
+    /// Whether wall time histogram is emitted.
+    pub fn wall(&self) -> bool {
+        matches!(self, Self::Wall | Self::Both)
+    }
+
+    /// Whether thread CPU time histogram is emitted.
+    pub fn thread_cpu(&self) -> bool {
+        matches!(self, Self::ThreadCpu | Self::Both)
+    }
+}
+
+/// CPU time consumed by the current thread, using `CLOCK_THREAD_CPUTIME_ID`.
+///
+/// It is only available on Linux, `None` is returned on other platforms.
+#[cfg(target_os = "linux")]
+pub fn thread_cpu_time() -> Option<Duration> {
+    let mut time = libc::timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `time` is a valid and writable timespec.
+    let result = unsafe { libc::clock_gettime(libc::CLOCK_THREAD_CPUTIME_ID, &mut time) };
+
+    (result == 0).then(|| Duration::new(time.tv_sec as u64, time.tv_nsec as u32))
+}
+
+/// CPU time consumed by the current thread, using `CLOCK_THREAD_CPUTIME_ID`.
+///
+/// It is only available on Linux, `None` is returned on other platforms.
+#[cfg(not(target_os = "linux"))]
+pub fn thread_cpu_time() -> Option<Duration> {
+    None
+}
@@ -0,0 +1,83 @@
+//! Folded stacks of timed calls, for flamegraphs.
+//!
+//! While capturing, each non-async timed call adds its self time (excluding nested timed
+//! calls) to the stack of timed functions it runs in. The result is written as collapsed stack
+//! lines, such as `main;handle;db_query 1234`, with values in microseconds, which can be
+//! fed directly to [inferno](https://github.com/jonhoo/inferno) or `flamegraph.pl`.
+//!
+//! ```
+//! use function_timer::time;
+//!
+//! #[time("my_metric")]
+//! fn handle() {
+//!     db_query();
+//! }
+//!
+//! #[time("my_metric")]
+//! fn db_query() {}
+//!
+//! function_timer::folded::start();
+//! handle();
+//! let stacks = function_timer::folded::stop();
+//!
+//! let lines: Vec<String> = stacks.to_string().lines().map(str::to_string).collect();
+//! assert!(lines[0].starts_with("handle "));
+//! assert!(lines[1].starts_with("handle;db_query "));
+//! ```
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::io::Write;
+use std::time::Duration;
+
+use crate::global::Capture;
+
+static STACKS: Capture<BTreeMap<String, Duration>> = Capture::new(BTreeMap::new());
+
+/// Start capturing stacks, discarding previously captured ones.
+pub fn start() {
+    STACKS.start();
+}
+
+/// Stop capturing and return captured stacks.
+pub fn stop() -> FoldedStacks {
+    FoldedStacks {
+        stacks: STACKS.stop(),
+    }
+}
+
+/// Whether stacks are being captured.
+pub fn is_capturing() -> bool {
+    STACKS.is_capturing()
+}
+
+/// Add self time to a stack if capturing.
+pub(crate) fn record(path: String, self_time: Duration) {
+    STACKS.record(|stacks| *stacks.entry(path).or_default() += self_time);
+}
+
+/// Self time per stack of timed functions, separated by `;`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FoldedStacks {
+    stacks: BTreeMap<String, Duration>,
+}
+
+impl FoldedStacks {
+    /// Self time per stack, sorted by stack.
+    pub fn stacks(&self) -> &BTreeMap<String, Duration> {
+        &self.stacks
+    }
+
+    /// Write one `stack microseconds` line per stack.
+    pub fn write(&self, mut writer: impl Write) -> std::io::Result<()> {
+        write!(writer, "{self}")
+    }
+}
+
+impl Display for FoldedStacks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (stack, self_time) in &self.stacks {
+            writeln!(f, "{stack} {}", self_time.as_micros())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+//! Global state shared by timers of all threads.
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+
+/// Setting read by every timer. An atomic flag avoids locking while it has its default value.
+pub(crate) struct Setting<T> {
+    custom: AtomicBool,
+    value: RwLock<T>,
+}
+
+impl<T> Setting<T> {
+    pub(crate) const fn new(value: T) -> Self {
+        Self {
+            custom: AtomicBool::new(false),
+            value: RwLock::new(value),
+        }
+    }
+
+    /// Update the value. `update` returns whether it differs from the default.
+    pub(crate) fn update(&self, update: impl FnOnce(&mut T) -> bool) {
+        let mut value = self
+            .value
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let custom = update(&mut value);
+        self.custom.store(custom, Ordering::Relaxed);
+    }
+
+    /// Whether the value differs from the default, without locking.
+    pub(crate) fn is_custom(&self) -> bool {
+        self.custom.load(Ordering::Relaxed)
+    }
+
+    /// Read the value.
+    pub(crate) fn read<R>(&self, read: impl FnOnce(&T) -> R) -> R {
+        let value = self
+            .value
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        read(&value)
+    }
+}
+
+/// Data filled by timers between [Capture::start] and [Capture::stop].
+pub(crate) struct Capture<T> {
+    capturing: AtomicBool,
+    data: Mutex<T>,
+}
+
+impl<T: Default> Capture<T> {
+    pub(crate) const fn new(data: T) -> Self {
+        Self {
+            capturing: AtomicBool::new(false),
+            data: Mutex::new(data),
+        }
+    }
+
+    /// Start capturing, discarding previously captured data.
+    pub(crate) fn start(&self) {
+        let mut data = self.lock();
+        *data = T::default();
+        self.capturing.store(true, Ordering::Relaxed);
+    }
+
+    /// Stop capturing and return captured data.
+    pub(crate) fn stop(&self) -> T {
+        self.capturing.store(false, Ordering::Relaxed);
+        std::mem::take(&mut *self.lock())
+    }
+
+    pub(crate) fn is_capturing(&self) -> bool {
+        self.capturing.load(Ordering::Relaxed)
+    }
+
+    /// Update captured data if capturing.
+    pub(crate) fn record(&self, record: impl FnOnce(&mut T)) {
+        let mut data = self.lock();
+        // Capture may have been stopped in the meantime
+        if self.is_capturing() {
+            record(&mut data);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, T> {
+        self.data
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
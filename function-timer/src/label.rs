@@ -0,0 +1,57 @@
+//! Keys of the tags added to metrics.
+use crate::global::Setting;
+
+static LABEL_KEYS: Setting<LabelKeys> = Setting::new(LabelKeys::DEFAULT);
+
+/// Keys of the tags holding the struct and function names.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LabelKeys {
+    /// Key of the tag holding the struct name.
+    pub struct_key: &'static str,
+    /// Key of the tag holding the function name.
+    pub function_key: &'static str,
+}
+
+impl LabelKeys {
+    /// `struct` and `function`.
+    pub const DEFAULT: LabelKeys = LabelKeys::new("struct", "function");
+
+    /// OpenTelemetry semantic conventions : `code.namespace` and `code.function`.
+    pub const OPEN_TELEMETRY: LabelKeys = LabelKeys::new("code.namespace", "code.function");
+
+    /// Create new label keys.
+    ///
+    /// # Parameters
+    ///
+    /// * `struct_key` : key of the tag holding the struct name.
+    /// * `function_key` : key of the tag holding the function name.
+    pub const fn new(struct_key: &'static str, function_key: &'static str) -> Self {
+        Self {
+            struct_key,
+            function_key,
+        }
+    }
+}
+
+impl Default for LabelKeys {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Set the label keys used by all timers, unless overridden in the `time` attribute.
+pub fn set_label_keys(keys: LabelKeys) {
+    LABEL_KEYS.update(|current| {
+        *current = keys;
+        keys != LabelKeys::DEFAULT
+    });
+}
+
+/// Label keys used by all timers, unless overridden in the `time` attribute.
+pub fn label_keys() -> LabelKeys {
+    if !LABEL_KEYS.is_custom() {
+        return LabelKeys::DEFAULT;
+    }
+
+    LABEL_KEYS.read(|keys| *keys)
+}
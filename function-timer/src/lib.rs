@@ -11,6 +11,171 @@
 //!
 //! Note that `#[time(disable)]` can't be on an `impl` block.
 //!
+//! # Features
+//!
+//! * `tracing` : `time` also opens a [tracing](https://crates.io/crates/tracing) span named
+//!   after the function, with `struct` and `function` fields. The execution time in seconds
+//!   is recorded in the `elapsed` field of the span. The span is entered for the duration of
+//!   non-async functions, and each time the body of async functions is polled.
+//! * `log` : slow calls (see `slow` option) are reported using [log](https://crates.io/crates/log).
+//!   When `tracing` is also enabled, they are reported as `tracing` events instead.
+//! * `quanta` : measure wall time with [quanta](https://crates.io/crates/quanta) instead of
+//!   [Instant](std::time::Instant).
+//! * `serde` : [report::Report] can be written and read as JSON or CSV.
+//! * `test-util` : `testing` module with a mock clock and helpers to capture and assert
+//!   recorded metrics.
+//!
+//! # Removing timers at compile time
+//!
+//! When the `FUNCTION_TIMER_DISABLED` environment variable is set (to anything but `0`) while
+//! building, or with `RUSTFLAGS="--cfg function_timer_disabled"`, `time` leaves functions
+//! untouched, so there is no overhead at all. The attribute is still checked, but constants
+//! used as metric names are no longer used.
+//!
+//! It applies to the whole build, so it is up to whoever builds the final binary : unlike a
+//! Cargo feature, a dependency can't turn it on. When cross-compiling with `--target`,
+//! `RUSTFLAGS` isn't passed to procedural macros, so use the environment variable.
+//!
+//! # Slow calls
+//!
+//! A threshold can be set with `#[time("my_metric", slow = "250ms")]`. When the execution is
+//! slower, a warning is emitted (`log` or `tracing` feature). Adding `slow_counter = true` also
+//! increments a `my_metric_slow_total` counter with the same tags as the histogram. Without
+//! `log` nor `tracing`, `slow` fails to compile unless `slow_counter = true` is set.
+//!
+//! # SLO violations
+//!
+//! Latency objectives can be set with `#[time("my_metric", slo = ["100ms", "1s"])]`. Each call
+//! above a threshold increments a `my_metric_slo_violations_total` counter with the same tags as
+//! the histogram and an additional `threshold` tag (e.g. `threshold="100ms"`). Durations are
+//! checked when the macro is expanded.
+//!
+//! # Sampling
+//!
+//! To reduce overhead on hot paths, only a fraction of calls can be timed :
+//! * `#[time("my_metric", sample = 0.01)]` times 1% of calls, randomly.
+//! * `#[time("my_metric", every = 100)]` times one call out of 100 on each thread.
+//!
+//! Calls that aren't sampled don't read the clock. Adding `count = true` increments a
+//! `my_metric_calls_total` counter for every call, sampled or not. Unless there's a `caller`
+//! tag, the counter handle is cached like a [cached histogram](#cached-histogram).
+//!
+//! # Cached histogram
+//!
+//! By default, the histogram is looked up in the recorder on every call. With
+//! `#[time("my_metric", cached = true)]`, the histogram handle is stored in a static the first
+//! time the function returns and reused afterward. The handle is bound to the recorder in use at
+//! that time, so this shouldn't be used with [metrics::with_local_recorder]. Cached handles are
+//! ignored within `testing::capture`.
+//!
+//! Install the global recorder before the first call : if there's none yet, the cached handle
+//! is a no-op one, and the function is never recorded afterward.
+//!
+//! # Conditional timing
+//!
+//! `#[time("my_metric", when = batch.len() > 1000)]` only times calls for which the expression
+//! is `true`. The expression can use the function arguments and is evaluated once, when entering
+//! the function.
+//!
+//! # Metric name
+//!
+//! A literal metric name can contain placeholders, resolved when the macro is expanded so the
+//! name is still a `&'static str` :
+//! * `{function}` : name of the function.
+//! * `{module}` : module path, as given by [module_path!], with `::` replaced by `_`.
+//! * `{struct}` : name of the struct, only when `time` is on an `impl` block.
+//! * `{trait}` : name of the trait, only when `time` is on a trait `impl` block.
+//!
+//! For instance, `#[time("{struct}_{function}_seconds")]` on an `impl` block gives one metric
+//! per method.
+//!
+//! A namespace can be set with `#[time("query_seconds", namespace = "db")]`, giving a
+//! `db_query_seconds` metric. A prefix for all metrics can be set at runtime with
+//! [set_prefix], e.g. `set_prefix("billing_")` gives `billing_db_query_seconds`. A cached
+//! histogram keeps the name it had when registered.
+//!
+//! # Label keys
+//!
+//! Tags are `struct` and `function` by default. They can be changed for all timers with
+//! [set_label_keys], for instance with the OpenTelemetry preset
+//! [LabelKeys::OPEN_TELEMETRY] (`code.namespace` and `code.function`), or in the attribute with
+//! `struct_label = "type"`, `function_label = "method"` or `label_keys = "opentelemetry"`.
+//!
+//! # Clock
+//!
+//! Wall time is read from a [Clock]. It is [InstantClock] by default (or `QuantaClock` with the
+//! `quanta` feature) and can be replaced, once, with [set_clock]. With the `test-util` feature,
+//! `testing::MockClock` allows tests to advance time deterministically.
+//!
+//! # CPU time
+//!
+//! Wall time hides whether a function is CPU-bound or blocked. With
+//! `#[time("my_metric", clock = "thread_cpu")]`, the CPU time of the current thread is emitted
+//! in a `my_metric_cpu` histogram with the same tags, instead of the wall time. Use
+//! `clock = "both"` to emit both histograms. Thread CPU time is only available on Linux, other
+//! platforms emit the wall time histogram instead. It doesn't make much sense for async
+//! functions that can move between threads. Slow calls and SLO are still checked against wall
+//! time.
+//!
+//! # Self time
+//!
+//! When timed functions call other timed functions, the parent histogram includes the time
+//! spent in children. `#[time("my_metric", self_time = true)]` also emits a
+//! `my_metric_self_seconds` histogram with the time spent in the function itself, excluding
+//! nested timed functions. Nesting is tracked per thread, so it can't be used on async
+//! functions.
+//!
+//! # Caller
+//!
+//! `#[time("my_metric", caller = true)]` adds a `caller` tag with the name of the enclosing
+//! timed function (`Struct::function` for methods), or [ROOT_CALLER] if there is none. A depth
+//! can be given instead, such as `caller = 2`, to get up to 2 enclosing functions, outermost
+//! first and separated by `/`. Keep it small to bound cardinality. Async functions are never
+//! callers.
+//!
+//! # Recursion
+//!
+//! By default, each call of a recursive timed function records a sample. With
+//! `#[time("my_metric", recursion = "outermost")]`, only the outermost call on the current
+//! thread is recorded. Adding `recursion_depth = true` also records the maximum recursion
+//! depth of each outermost call in a `my_metric_recursion_depth` histogram. Both options
+//! can't be used on async functions.
+//!
+//! # Cardinality guard
+//!
+//! [set_cardinality_limit] caps the number of distinct label sets per metric name. Above the
+//! cap, tag values are replaced by `__other__` and the
+//! `function_timer_cardinality_overflow_total` counter is incremented.
+//!
+//! # Trace
+//!
+//! Between [trace::start] and [trace::stop], timed calls are captured in memory and can be
+//! written in the Trace Event Format, to get a timeline in `chrome://tracing` or Perfetto.
+//!
+//! # Flamegraph
+//!
+//! Between [folded::start] and [folded::stop], the self time of nested timed functions is
+//! aggregated per call stack, and can be written as folded stacks for `inferno` or
+//! `flamegraph.pl`. Only non-async functions are part of stacks.
+//!
+//! # Runtime switch
+//!
+//! Timing can be turned off without redeploying, either globally with [set_enabled] or for a
+//! single metric with [set_metric_enabled], using the name given to `time` with its namespace,
+//! but without prefix. Disabled timers don't read the clock nor emit any
+//! metric.
+//!
+//! # Report
+//!
+//! For CLI tools and benchmarks, [report::ReportRecorder] aggregates execution times in memory
+//! and prints a summary table instead of exporting metrics. [install_report_on_exit] prints it
+//! to stderr, or to the file named by the `FUNCTION_TIMER_REPORT` environment variable, when
+//! the returned guard is dropped.
+//!
+//! [compare] lists p50 and p99 of a report that regressed compared to a baseline. With the
+//! `serde` feature, the `function-timer-compare` binary does the same on reports written as
+//! JSON or CSV, and exits with a non-zero code on regression, to gate CI on latency.
+//!
 //! # Example
 //!
 //! * On functions and methods :
@@ -118,17 +283,84 @@
 //!     Ok(())
 //! }
 //! ```
-use std::time::Instant;
+use std::sync::OnceLock;
+use std::time::Duration;
 
 pub use function_timer_macro::time;
-use metrics::histogram;
+#[doc(hidden)]
+pub use metrics;
+use metrics::{
+    counter, describe_histogram, histogram, Counter, Histogram, Label, SharedString, Unit,
+};
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+pub use tracing;
+
+pub use crate::cardinality::{
+    cardinality_limit, set_cardinality_limit, OVERFLOW_COUNTER, OVERFLOW_VALUE,
+};
+#[cfg(feature = "quanta")]
+pub use crate::clock::QuantaClock;
+pub use crate::clock::{set_clock, Clock, InstantClock, SetClockError};
+pub use crate::compare::{compare, Comparison, Regression};
+pub use crate::cpu::{thread_cpu_time, ClockMode};
+pub use crate::label::{label_keys, set_label_keys, LabelKeys};
+pub use crate::name::{prefix, set_prefix};
+#[doc(hidden)]
+pub use crate::name::{template_bytes, template_len, template_str};
+pub use crate::report::{install_report_on_exit, ReportGuard};
+pub use crate::sampling::Sampling;
+pub use crate::stack::ROOT_CALLER;
+pub use crate::switch::{is_enabled, is_metric_enabled, set_enabled, set_metric_enabled};
+
+mod cardinality;
+mod clock;
+mod compare;
+mod cpu;
+pub mod folded;
+mod global;
+mod label;
+mod name;
+pub mod report;
+mod sampling;
+mod stack;
+mod switch;
+#[cfg(feature = "test-util")]
+pub mod testing;
+pub mod trace;
+
+/// Whether handles cached per call site must be ignored, as metrics go to `testing::capture`.
+fn ignore_caches() -> bool {
+    #[cfg(feature = "test-util")]
+    return testing::is_capturing();
+    #[cfg(not(feature = "test-util"))]
+    false
+}
 
 /// Timer.
 pub struct FunctionTimer {
     metric_name: &'static str,
     struct_name: Option<&'static str>,
     function: &'static str,
-    chrono: Instant,
+    struct_key: Option<&'static str>,
+    function_key: Option<&'static str>,
+    enabled: bool,
+    /// Clock the timer started with, so that it ends with the same one.
+    clock: &'static dyn Clock,
+    chrono: Option<Duration>,
+    wall: bool,
+    cpu_chrono: Option<Duration>,
+    slow_threshold: Option<Duration>,
+    slow_counter: bool,
+    slo: &'static [(Duration, &'static str)],
+    histogram: Option<&'static OnceLock<Histogram>>,
+    frame: Option<u64>,
+    self_time: bool,
+    caller: Option<String>,
+    outermost: Option<u64>,
+    recursion_depth: bool,
+    #[cfg(feature = "tracing")]
+    span: tracing::Span,
 }
 
 impl FunctionTimer {
@@ -145,24 +377,393 @@ impl FunctionTimer {
         struct_name: Option<&'static str>,
         function: &'static str,
     ) -> Self {
+        Self::new_sampled(metric_name, struct_name, function, true)
+    }
+
+    /// Create a new [FunctionTimer] that only times the call if `sampled` is `true`.
+    ///
+    /// # Parameters
+    ///
+    /// * `metric_name` : name of the metric.
+    /// * `struct_name` : name of the struct.
+    /// * `function` : name of the function that have the annotation. It is used to generate
+    ///   the tag `function`.
+    /// * `sampled` : whether this call is timed (see [Sampling]).
+    ///
+    /// Nothing is timed if the metric is disabled (see [set_enabled] and [set_metric_enabled]).
+    pub fn new_sampled(
+        metric_name: &'static str,
+        struct_name: Option<&'static str>,
+        function: &'static str,
+        sampled: bool,
+    ) -> Self {
+        let enabled = is_metric_enabled(metric_name);
+        let clock = clock::current();
         Self {
             metric_name,
             struct_name,
             function,
-            chrono: Instant::now(),
+            struct_key: None,
+            function_key: None,
+            enabled,
+            clock,
+            chrono: (enabled && sampled).then(|| clock.now()),
+            wall: true,
+            cpu_chrono: None,
+            slow_threshold: None,
+            slow_counter: false,
+            slo: &[],
+            histogram: None,
+            frame: None,
+            self_time: false,
+            caller: None,
+            outermost: None,
+            recursion_depth: false,
+            #[cfg(feature = "tracing")]
+            span: tracing::Span::none(),
+        }
+    }
+
+    /// Override the label keys set with [set_label_keys].
+    pub fn with_label_keys(self, keys: LabelKeys) -> Self {
+        self.with_struct_label(keys.struct_key)
+            .with_function_label(keys.function_key)
+    }
+
+    /// Override the key of the tag holding the struct name.
+    pub fn with_struct_label(mut self, key: &'static str) -> Self {
+        self.struct_key = Some(key);
+        self
+    }
+
+    /// Override the key of the tag holding the function name.
+    pub fn with_function_label(mut self, key: &'static str) -> Self {
+        self.function_key = Some(key);
+        self
+    }
+
+    /// Label keys, taking overrides into account.
+    fn label_keys(&self) -> LabelKeys {
+        if let (Some(struct_key), Some(function_key)) = (self.struct_key, self.function_key) {
+            return LabelKeys::new(struct_key, function_key);
+        }
+        let keys = label_keys();
+        LabelKeys::new(
+            self.struct_key.unwrap_or(keys.struct_key),
+            self.function_key.unwrap_or(keys.function_key),
+        )
+    }
+
+    /// Name of the metric, with prefix.
+    fn name(&self) -> SharedString {
+        name::compose(self.metric_name, "")
+    }
+
+    /// Name of the metric, with prefix and `suffix`.
+    fn suffixed_name(&self, suffix: &'static str) -> SharedString {
+        name::compose(self.metric_name, suffix)
+    }
+
+    /// Report calls slower than `threshold`.
+    ///
+    /// # Parameters
+    ///
+    /// * `threshold` : execution time above which a call is considered slow.
+    /// * `counter` : if `true`, slow calls also increment a `<metric_name>_slow_total` counter.
+    pub fn with_slow_threshold(mut self, threshold: Duration, counter: bool) -> Self {
+        self.slow_threshold = Some(threshold);
+        self.slow_counter = counter;
+        self
+    }
+
+    /// Count calls above service level objectives.
+    ///
+    /// # Parameters
+    ///
+    /// * `slo` : thresholds and their value for the `threshold` tag of the
+    ///   `<metric_name>_slo_violations_total` counter.
+    pub fn with_slo(mut self, slo: &'static [(Duration, &'static str)]) -> Self {
+        self.slo = slo;
+        self
+    }
+
+    /// Select the measured time. Thread CPU time is emitted in a `<metric_name>_cpu` histogram.
+    ///
+    /// Where thread CPU time isn't available, wall time is emitted instead.
+    pub fn with_clock_mode(mut self, mode: ClockMode) -> Self {
+        if mode.thread_cpu() && self.chrono.is_some() {
+            self.cpu_chrono = thread_cpu_time();
+        }
+        self.wall = mode.wall() || self.cpu_chrono.is_none();
+        self
+    }
+
+    /// Register the timer on the stack of active timers of the current thread, so that it knows
+    /// the time spent in nested timers.
+    ///
+    /// The timer must be dropped on the same thread, so it shouldn't be used in async functions.
+    pub fn with_call_stack(mut self) -> Self {
+        if self.chrono.is_some() && self.frame.is_none() {
+            self.frame = Some(stack::push(
+                self.metric_name,
+                self.struct_name,
+                self.function,
+            ));
+        }
+        self
+    }
+
+    /// Also emit a `<metric_name>_self_seconds` histogram, excluding time spent in nested
+    /// timers. It implies [FunctionTimer::with_call_stack].
+    pub fn with_self_time(mut self) -> Self {
+        self.self_time = true;
+        self.with_call_stack()
+    }
+
+    /// Add a `caller` tag with up to `depth` enclosing timed functions of the current thread,
+    /// outermost first and separated by `/`, or [ROOT_CALLER] if there is none.
+    ///
+    /// Only timers registered with [FunctionTimer::with_call_stack] are callers. The histogram
+    /// is looked up on each call, even with [FunctionTimer::with_cached_histogram].
+    pub fn with_caller(mut self, depth: usize) -> Self {
+        if self.enabled {
+            self.caller = Some(stack::callers(depth.max(1), self.frame));
+        }
+        self
+    }
+
+    /// Only record the outermost call of recursive calls on the current thread: nested timers
+    /// with the same metric name, struct and function record nothing. It implies
+    /// [FunctionTimer::with_call_stack].
+    ///
+    /// With `depth`, a `<metric_name>_recursion_depth` histogram gets the maximum recursion
+    /// depth of each outermost call, `1` meaning no recursion.
+    pub fn with_outermost_only(mut self, depth: bool) -> Self {
+        if self.chrono.is_none() || self.frame.is_some() {
+            return self;
+        }
+        self.outermost = stack::recurse(self.metric_name, self.struct_name, self.function);
+        if self.outermost.is_some() {
+            self.chrono = None;
+            self.cpu_chrono = None;
+            return self;
+        }
+        self.recursion_depth = depth;
+        self.with_call_stack()
+    }
+
+    /// Increment a `<metric_name>_calls_total` counter, whether the call is sampled or not.
+    ///
+    /// Like [FunctionTimer::with_cached_histogram], the counter is stored in `cache` the first
+    /// time and reused afterward, unless there's a `caller` tag.
+    pub fn with_call_counter(self, cache: &'static OnceLock<Counter>) -> Self {
+        if !self.enabled {
+            return self;
+        }
+        if self.caller.is_some() || ignore_caches() {
+            self.counter("_calls_total", self.labels()).increment(1);
+        } else {
+            cache
+                .get_or_init(|| self.counter("_calls_total", self.labels()))
+                .increment(1);
+        }
+        self
+    }
+
+    /// Reuse the histogram stored in `cache`, registering it on first use.
+    ///
+    /// The cache must be specific to the metric name, struct and function of the timer.
+    pub fn with_cached_histogram(mut self, cache: &'static OnceLock<Histogram>) -> Self {
+        self.histogram = Some(cache);
+        self
+    }
+
+    /// Look up the histogram in the recorder.
+    fn histogram(&self) -> Histogram {
+        if self.caller.is_some() || cardinality::is_limited() {
+            let name = self.name();
+            let labels = cardinality::guard(&name, self.labels());
+            return histogram!(name, labels);
+        }
+
+        let keys = self.label_keys();
+        if let Some(struct_name) = self.struct_name {
+            histogram!(self.name(),  keys.struct_key => struct_name, keys.function_key => self.function)
+        } else {
+            histogram!(self.name(),  keys.function_key => self.function)
+        }
+    }
+
+    /// Histogram of time spent in the function itself.
+    fn self_histogram(&self) -> Histogram {
+        let name = self.suffixed_name("_self_seconds");
+        let labels = cardinality::guard(&name, self.labels());
+        histogram!(name, labels)
+    }
+
+    /// Histogram of recursion depth.
+    fn recursion_depth_histogram(&self) -> Histogram {
+        let name = self.suffixed_name("_recursion_depth");
+        // Not a duration, so that recorders such as `ReportRecorder` can tell
+        describe_histogram!(
+            name.clone(),
+            Unit::Count,
+            "Maximum recursion depth of outermost calls"
+        );
+        let labels = cardinality::guard(&name, self.labels());
+        histogram!(name, labels)
+    }
+
+    /// Histogram of thread CPU time.
+    fn cpu_histogram(&self) -> Histogram {
+        let name = self.suffixed_name("_cpu");
+        let labels = cardinality::guard(&name, self.labels());
+        histogram!(name, labels)
+    }
+
+    /// Counter `<metric_name><suffix>`, with tags checked by the cardinality guard.
+    fn counter(&self, suffix: &'static str, labels: Vec<Label>) -> Counter {
+        let name = self.suffixed_name(suffix);
+        let labels = cardinality::guard(&name, labels);
+        counter!(name, labels)
+    }
+
+    /// Tags `struct` and `function`, or their overrides, and `caller`.
+    fn labels(&self) -> Vec<Label> {
+        let keys = self.label_keys();
+        let mut labels = Vec::with_capacity(4);
+        if let Some(struct_name) = self.struct_name {
+            labels.push(Label::from_static_parts(keys.struct_key, struct_name));
+        }
+        labels.push(Label::from_static_parts(keys.function_key, self.function));
+        if let Some(caller) = &self.caller {
+            labels.push(Label::new("caller", caller.clone()));
+        }
+        labels
+    }
+
+    /// Increment violations counter for each exceeded SLO.
+    fn report_slo(&self, elapsed: Duration) {
+        for (threshold, label) in self.slo {
+            if elapsed > *threshold {
+                let mut labels = self.labels();
+                labels.push(Label::from_static_parts("threshold", label));
+                self.counter("_slo_violations_total", labels).increment(1);
+            }
+        }
+    }
+
+    /// Warn and increment the slow calls counter if needed.
+    fn report_slow(&self, elapsed: Duration) {
+        let threshold = match self.slow_threshold {
+            Some(threshold) if elapsed > threshold => threshold,
+            _ => return,
+        };
+
+        #[cfg(feature = "tracing")]
+        tracing::warn!(
+            metric = &*self.name(),
+            "struct" = self.struct_name,
+            function = self.function,
+            elapsed = ?elapsed,
+            threshold = ?threshold,
+            "Slow call"
+        );
+        #[cfg(all(feature = "log", not(feature = "tracing")))]
+        match self.struct_name {
+            Some(struct_name) => log::warn!(
+                "Slow call : metric={}, struct={}, function={}, elapsed={:?}, threshold={:?}",
+                self.name(),
+                struct_name,
+                self.function,
+                elapsed,
+                threshold
+            ),
+            None => log::warn!(
+                "Slow call : metric={}, function={}, elapsed={:?}, threshold={:?}",
+                self.name(),
+                self.function,
+                elapsed,
+                threshold
+            ),
+        }
+        #[cfg(not(any(feature = "log", feature = "tracing")))]
+        let _ = threshold;
+
+        if self.slow_counter {
+            self.counter("_slow_total", self.labels()).increment(1);
         }
     }
 }
 
+#[cfg(feature = "tracing")]
+impl FunctionTimer {
+    /// Attach a span to the timer. Execution time, in seconds, will be recorded
+    /// in its `elapsed` field.
+    pub fn with_span(mut self, span: tracing::Span) -> Self {
+        self.span = span;
+        self
+    }
+
+    /// Span attached to the timer.
+    pub fn span(&self) -> &tracing::Span {
+        &self.span
+    }
+}
+
 impl Drop for FunctionTimer {
     /// Get execution time and call [`histogram!`](histogram).
     fn drop(&mut self) {
-        let d = self.chrono.elapsed();
-        let histogram = if let Some(struct_name) = self.struct_name {
-            histogram!(self.metric_name,  "struct" => struct_name, "function" => self.function)
-        } else {
-            histogram!(self.metric_name,  "function" => self.function)
+        if let Some(outermost) = self.outermost {
+            stack::unwind(outermost);
+        }
+        let start = match self.chrono {
+            Some(chrono) => chrono,
+            None => return,
         };
-        histogram.record(d);
+        let d = self.clock.now().saturating_sub(start);
+        if self.wall {
+            if let (Some(cache), None, false) = (self.histogram, &self.caller, ignore_caches()) {
+                cache.get_or_init(|| self.histogram()).record(d);
+            } else {
+                self.histogram().record(d);
+            }
+        }
+        let path = self
+            .frame
+            .filter(|_| folded::is_capturing())
+            .and_then(stack::path);
+        if let Some(frame) = self.frame.and_then(|id| stack::pop(id, d)) {
+            if let Some(path) = path {
+                folded::record(path, d.saturating_sub(frame.children));
+            }
+            if self.self_time {
+                self.self_histogram()
+                    .record(d.saturating_sub(frame.children));
+            }
+            if self.recursion_depth {
+                self.recursion_depth_histogram()
+                    .record(frame.max_depth as f64);
+            }
+        }
+        if let Some(start) = self.cpu_chrono {
+            if let Some(end) = thread_cpu_time() {
+                self.cpu_histogram().record(end.saturating_sub(start));
+            }
+        }
+        if trace::is_capturing() {
+            trace::record(
+                &self.name(),
+                self.struct_name,
+                self.function,
+                start,
+                d,
+                self.labels(),
+            );
+        }
+        self.report_slow(d);
+        self.report_slo(d);
+        #[cfg(feature = "tracing")]
+        self.span.record("elapsed", d.as_secs_f64());
     }
 }
@@ -0,0 +1,128 @@
+//! Composition of metric names with a prefix, and of namespaced names at compile time.
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use metrics::SharedString;
+
+use crate::global::Setting;
+
+static PREFIX: Setting<String> = Setting::new(String::new());
+
+/// Set a prefix added to all metric names (e.g. `billing_`).
+///
+/// An empty prefix removes it.
+pub fn set_prefix(prefix: &str) {
+    PREFIX.update(|current| {
+        current.clear();
+        current.push_str(prefix);
+        !prefix.is_empty()
+    });
+}
+
+/// Prefix added to all metric names.
+pub fn prefix() -> String {
+    if !PREFIX.is_custom() {
+        return String::new();
+    }
+
+    PREFIX.read(String::clone)
+}
+
+/// Final metric name : `<prefix><metric_name><suffix>`.
+///
+/// The namespace is already part of `metric_name`, as `time` adds it at compile time. It doesn't
+/// allocate if there's no prefix.
+pub(crate) fn compose(metric_name: &'static str, suffix: &'static str) -> SharedString {
+    if PREFIX.is_custom() {
+        return SharedString::from(format!("{}{metric_name}{suffix}", prefix()));
+    }
+    if suffix.is_empty() {
+        return SharedString::const_str(metric_name);
+    }
+
+    SharedString::const_str(suffixed(metric_name, suffix))
+}
+
+/// Names with a suffix, built once for the whole program.
+static SUFFIXED: Mutex<BTreeMap<(&str, &str), &str>> = Mutex::new(BTreeMap::new());
+
+thread_local! {
+    /// Names already looked up by the current thread, so that [SUFFIXED] is rarely locked.
+    static LOCAL_SUFFIXED: RefCell<BTreeMap<(&'static str, &'static str), &'static str>> =
+        const { RefCell::new(BTreeMap::new()) };
+}
+
+/// `<metric_name><suffix>`, leaked once as there are only as many as timed functions.
+fn suffixed(metric_name: &'static str, suffix: &'static str) -> &'static str {
+    LOCAL_SUFFIXED.with(|local| {
+        *local
+            .borrow_mut()
+            .entry((metric_name, suffix))
+            .or_insert_with(|| {
+                let mut names = SUFFIXED
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner());
+                names
+                    .entry((metric_name, suffix))
+                    .or_insert_with(|| Box::leak(format!("{metric_name}{suffix}").into_boxed_str()))
+            })
+    })
+}
+
+/// Whether `bytes[i]` starts a `::` separator.
+const fn is_separator(bytes: &[u8], i: usize) -> bool {
+    bytes[i] == b':' && i + 1 < bytes.len() && bytes[i + 1] == b':'
+}
+
+/// Length of the metric name made of `parts`, see [template_bytes].
+#[doc(hidden)]
+pub const fn template_len(parts: &[&str]) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        let bytes = parts[i].as_bytes();
+        let mut j = 0;
+        while j < bytes.len() {
+            j += if is_separator(bytes, j) { 2 } else { 1 };
+            len += 1;
+        }
+        i += 1;
+    }
+    len
+}
+
+/// Metric name made of `parts`, with `::` replaced by `_`.
+///
+/// Used by `time` to resolve `{module}` at compile time, with `N` given by [template_len].
+#[doc(hidden)]
+pub const fn template_bytes<const N: usize>(parts: &[&str]) -> [u8; N] {
+    let mut name = [0; N];
+    let mut len = 0;
+    let mut i = 0;
+    while i < parts.len() {
+        let bytes = parts[i].as_bytes();
+        let mut j = 0;
+        while j < bytes.len() {
+            if is_separator(bytes, j) {
+                name[len] = b'_';
+                j += 2;
+            } else {
+                name[len] = bytes[j];
+                j += 1;
+            }
+            len += 1;
+        }
+        i += 1;
+    }
+    name
+}
+
+/// Metric name built by [template_bytes].
+#[doc(hidden)]
+pub const fn template_str(bytes: &'static [u8]) -> &'static str {
+    match std::str::from_utf8(bytes) {
+        Ok(name) => name,
+        Err(_) => panic!("Invalid metric name"),
+    }
+}
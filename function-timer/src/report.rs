@@ -0,0 +1,720 @@
+//! In-process aggregation of execution times, without any external recorder.
+//!
+//! [ReportRecorder] is a [Recorder] keeping a summary of each histogram in memory. Once the
+//! work is done, [ReportRecorder::report] gives a [Report] with count, total, mean, min, max
+//! and percentiles per metric and label set, which prints as a table sorted by total time.
+//!
+//! Struct and function are found by position, as timers put them before any other tag,
+//! whatever their keys.
+//!
+//! Memory doesn't grow with the number of values: they are counted in buckets, like
+//! [HdrHistogram](http://hdrhistogram.org/), so percentiles are within about 2% of the exact
+//! value. Count, total, min and max are exact, to the nanosecond.
+//!
+//! ```
+//! use function_timer::report::ReportRecorder;
+//! use function_timer::time;
+//!
+//! #[time("my_metric")]
+//! fn work() {}
+//!
+//! let recorder = ReportRecorder::new();
+//! metrics::with_local_recorder(&recorder, || {
+//!     work();
+//!     work();
+//! });
+//!
+//! let report = recorder.report();
+//! assert_eq!(report.rows()[0].count, 2);
+//! println!("{report}");
+//! ```
+//!
+//! Counters, gauges and histograms described with a unit other than seconds, such as
+//! `<metric_name>_recursion_depth`, are ignored.
+//!
+//! With the `serde` feature, a [Report] can be written and read back as JSON or CSV, so runs
+//! can be compared. Durations are in seconds.
+//!
+//! For ad-hoc profiling, [install_report_on_exit] installs the recorder globally and prints the
+//! report when the returned guard is dropped, usually at the end of `main` :
+//!
+//! ```no_run
+//! # use std::error::Error;
+//! fn main() -> Result<(), Box<dyn Error>> {
+//!     let _report = function_timer::install_report_on_exit()?;
+//!
+//!     // Timed work...
+//!     Ok(())
+//! }
+//! ```
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+#[cfg(feature = "serde")]
+use std::io::Read;
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use metrics::{
+    Counter, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder, SetRecorderError,
+    SharedString, Unit,
+};
+
+/// Significant bits of bucketed values.
+const SUB_BUCKET_BITS: u32 = 5;
+/// Buckets per power of 2.
+const SUB_BUCKETS: usize = 1 << SUB_BUCKET_BITS;
+/// One bucket per nanosecond below [SUB_BUCKETS], then [SUB_BUCKETS] per power of 2.
+const BUCKETS: usize = (u64::BITS - SUB_BUCKET_BITS + 1) as usize * SUB_BUCKETS;
+
+/// Summary of the values recorded by one histogram, in nanoseconds.
+pub(crate) struct HistogramSummary {
+    count: AtomicU64,
+    sum: AtomicU64,
+    min: AtomicU64,
+    max: AtomicU64,
+    buckets: Box<[AtomicU64]>,
+}
+
+impl Default for HistogramSummary {
+    fn default() -> Self {
+        Self {
+            count: AtomicU64::new(0),
+            sum: AtomicU64::new(0),
+            min: AtomicU64::new(u64::MAX),
+            max: AtomicU64::new(0),
+            buckets: (0..BUCKETS).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+}
+
+impl HistogramSummary {
+    fn clear(&self) {
+        self.count.store(0, Ordering::Relaxed);
+        self.sum.store(0, Ordering::Relaxed);
+        self.min.store(u64::MAX, Ordering::Relaxed);
+        self.max.store(0, Ordering::Relaxed);
+        for bucket in self.buckets.iter() {
+            bucket.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+impl HistogramFn for HistogramSummary {
+    /// Record a value in seconds, negative or invalid values being zero.
+    fn record(&self, value: f64) {
+        let nanos = (value * 1e9).round() as u64;
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum.fetch_add(nanos, Ordering::Relaxed);
+        self.min.fetch_min(nanos, Ordering::Relaxed);
+        self.max.fetch_max(nanos, Ordering::Relaxed);
+        self.buckets[bucket(nanos)].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Bucket of a value.
+fn bucket(nanos: u64) -> usize {
+    if nanos < SUB_BUCKETS as u64 {
+        return nanos as usize;
+    }
+    let shift = u64::BITS - 1 - nanos.leading_zeros() - SUB_BUCKET_BITS;
+    (shift as usize + 1) * SUB_BUCKETS + (nanos >> shift) as usize - SUB_BUCKETS
+}
+
+/// Lower bound and width of a bucket.
+fn bucket_range(index: usize) -> (u64, u64) {
+    if index < SUB_BUCKETS {
+        return (index as u64, 1);
+    }
+    let shift = (index / SUB_BUCKETS - 1) as u32;
+    let lower = ((SUB_BUCKETS + index % SUB_BUCKETS) as u64) << shift;
+    (lower, 1 << shift)
+}
+
+/// [Recorder] aggregating histograms in memory.
+///
+/// Clones share the same values, so a clone can be installed as the global recorder while
+/// the original is kept to build the report.
+#[derive(Clone, Default)]
+pub struct ReportRecorder {
+    histograms: Arc<Mutex<BTreeMap<Key, Arc<HistogramSummary>>>>,
+    /// Histograms described with a unit other than seconds.
+    not_durations: Arc<Mutex<BTreeSet<String>>>,
+}
+
+impl ReportRecorder {
+    /// Create an empty recorder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Install a clone of this recorder as the global recorder.
+    pub fn install(&self) -> Result<(), SetRecorderError<Self>> {
+        metrics::set_global_recorder(self.clone())
+    }
+
+    /// Summary of the values recorded so far.
+    pub fn report(&self) -> Report {
+        let histograms = self
+            .histograms
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let not_durations = self
+            .not_durations
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut rows: Vec<Row> = histograms
+            .iter()
+            .filter(|(key, _)| !not_durations.contains(key.name()))
+            .filter_map(|(key, summary)| Row::new(key, summary))
+            .collect();
+        rows.sort_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)));
+        Report { rows }
+    }
+
+    /// Forget recorded values.
+    pub fn clear(&self) {
+        let histograms = self
+            .histograms
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        for summary in histograms.values() {
+            summary.clear();
+        }
+    }
+}
+
+impl Recorder for ReportRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, key: KeyName, unit: Option<Unit>, _: SharedString) {
+        let mut not_durations = self
+            .not_durations
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match unit {
+            Some(unit) if unit != Unit::Seconds => {
+                if !not_durations.contains(key.as_str()) {
+                    not_durations.insert(key.as_str().to_string());
+                }
+            }
+            _ => {
+                not_durations.remove(key.as_str());
+            }
+        }
+    }
+
+    fn register_counter(&self, _: &Key, _: &Metadata<'_>) -> Counter {
+        Counter::noop()
+    }
+
+    fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        let mut histograms = self
+            .histograms
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let summary = histograms.entry(key.clone()).or_default();
+        Histogram::from_arc(summary.clone())
+    }
+}
+
+/// Summary of one metric and label set.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "Record", from = "Record")
+)]
+pub struct Row {
+    /// Metric name.
+    pub name: String,
+    /// Struct name, for methods.
+    pub struct_name: Option<String>,
+    /// Function name.
+    pub function: Option<String>,
+    /// Labels other than struct and function, as `(key, value)`.
+    pub labels: Vec<(String, String)>,
+    /// Number of values.
+    pub count: u64,
+    /// Sum of values.
+    pub total: Duration,
+    /// Mean value.
+    pub mean: Duration,
+    /// Minimum value.
+    pub min: Duration,
+    /// Median.
+    pub p50: Duration,
+    /// 90th percentile.
+    pub p90: Duration,
+    /// 99th percentile.
+    pub p99: Duration,
+    /// Maximum value.
+    pub max: Duration,
+}
+
+impl Row {
+    /// Row of a histogram, `None` if it has no value.
+    fn new(key: &Key, summary: &HistogramSummary) -> Option<Self> {
+        let count = summary.count.load(Ordering::Relaxed);
+        if count == 0 {
+            return None;
+        }
+
+        let total = summary.sum.load(Ordering::Relaxed);
+        let min = summary.min.load(Ordering::Relaxed);
+        let max = summary.max.load(Ordering::Relaxed);
+        let buckets: Vec<u64> = summary
+            .buckets
+            .iter()
+            .map(|bucket| bucket.load(Ordering::Relaxed))
+            .collect();
+        // Middle of the bucket holding the value of rank `p * count`
+        let percentile = |p: f64| {
+            let rank = ((p * count as f64).ceil() as u64).clamp(1, count);
+            if rank == 1 {
+                return Duration::from_nanos(min);
+            }
+            if rank == count {
+                return Duration::from_nanos(max);
+            }
+            let mut seen = 0;
+            let nanos = buckets
+                .iter()
+                .enumerate()
+                .find_map(|(index, bucket)| {
+                    seen += bucket;
+                    (seen >= rank).then(|| {
+                        let (lower, width) = bucket_range(index);
+                        lower + (width - 1) / 2
+                    })
+                })
+                .unwrap_or(max);
+            Duration::from_nanos(nanos.clamp(min, max))
+        };
+
+        let (struct_name, function, labels) = split_labels(key);
+        Some(Row {
+            name: key.name().to_string(),
+            struct_name,
+            function,
+            labels,
+            count,
+            total: Duration::from_nanos(total),
+            mean: Duration::from_nanos(total / count),
+            min: Duration::from_nanos(min),
+            p50: percentile(0.5),
+            p90: percentile(0.9),
+            p99: percentile(0.99),
+            max: Duration::from_nanos(max),
+        })
+    }
+
+    /// Labels as `key=value`, separated by commas.
+    fn labels_text(&self) -> String {
+        self.labels
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// Struct, function and other labels of a histogram.
+///
+/// Timers tag histograms with the struct (for methods), the function and optionally the
+/// caller, in this order.
+fn split_labels(key: &Key) -> (Option<String>, Option<String>, Vec<(String, String)>) {
+    let mut labels: Vec<(String, String)> = key
+        .labels()
+        .map(|label| (label.key().to_string(), label.value().to_string()))
+        .collect();
+    let caller = match labels.last() {
+        Some((key, _)) if key == "caller" => labels.pop(),
+        _ => None,
+    };
+    let (struct_name, function) = match labels.len() {
+        1 => (None, labels.pop()),
+        2 => {
+            let function = labels.pop();
+            (labels.pop(), function)
+        }
+        _ => (None, None),
+    };
+    labels.extend(caller);
+    (
+        struct_name.map(|(_, value)| value),
+        function.map(|(_, value)| value),
+        labels,
+    )
+}
+
+/// Convert seconds to a duration, negative or invalid values being zero.
+#[cfg(feature = "serde")]
+fn seconds(value: f64) -> Duration {
+    Duration::try_from_secs_f64(value).unwrap_or_default()
+}
+
+/// Summary of recorded values, sorted by total time, slowest first.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct Report {
+    rows: Vec<Row>,
+}
+
+impl Report {
+    /// Rows of the report.
+    pub fn rows(&self) -> &[Row] {
+        &self.rows
+    }
+
+    /// Keep only the `n` slowest rows.
+    pub fn top(mut self, n: usize) -> Self {
+        self.rows.truncate(n);
+        self
+    }
+}
+
+impl Display for Report {
+    /// Print a table with a header line and one line per row.
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        const HEADER: [&str; 12] = [
+            "metric", "struct", "function", "labels", "count", "total", "mean", "min", "p50",
+            "p90", "p99", "max",
+        ];
+        let lines: Vec<[String; 12]> = self
+            .rows
+            .iter()
+            .map(|row| {
+                [
+                    row.name.clone(),
+                    row.struct_name.clone().unwrap_or_default(),
+                    row.function.clone().unwrap_or_default(),
+                    row.labels_text(),
+                    row.count.to_string(),
+                    format!("{:.2?}", row.total),
+                    format!("{:.2?}", row.mean),
+                    format!("{:.2?}", row.min),
+                    format!("{:.2?}", row.p50),
+                    format!("{:.2?}", row.p90),
+                    format!("{:.2?}", row.p99),
+                    format!("{:.2?}", row.max),
+                ]
+            })
+            .collect();
+
+        let mut widths = HEADER.map(str::len);
+        for line in &lines {
+            for (width, cell) in widths.iter_mut().zip(line) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let header = HEADER.map(str::to_string);
+        for line in std::iter::once(&header).chain(&lines) {
+            let mut cells = Vec::with_capacity(line.len());
+            for (i, (cell, width)) in line.iter().zip(widths).enumerate() {
+                // Text columns are left aligned, numbers right aligned
+                if i < 4 {
+                    cells.push(format!("{cell:<width$}"));
+                } else {
+                    cells.push(format!("{cell:>width$}"));
+                }
+            }
+            writeln!(f, "{}", cells.join("  ").trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Columns of the CSV export.
+#[cfg(feature = "serde")]
+const CSV_HEADER: [&str; 12] = [
+    "metric",
+    "struct",
+    "function",
+    "labels",
+    "count",
+    "total_seconds",
+    "mean_seconds",
+    "min_seconds",
+    "p50_seconds",
+    "p90_seconds",
+    "p99_seconds",
+    "max_seconds",
+];
+
+/// Serialized form of a [Row].
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Record {
+    metric: String,
+    #[serde(rename = "struct")]
+    struct_name: Option<String>,
+    function: Option<String>,
+    /// Labels other than struct and function.
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+    count: u64,
+    total_seconds: f64,
+    mean_seconds: f64,
+    min_seconds: f64,
+    p50_seconds: f64,
+    p90_seconds: f64,
+    p99_seconds: f64,
+    max_seconds: f64,
+}
+
+#[cfg(feature = "serde")]
+impl From<Row> for Record {
+    fn from(row: Row) -> Self {
+        Record {
+            metric: row.name,
+            struct_name: row.struct_name,
+            function: row.function,
+            labels: row.labels.into_iter().collect(),
+            count: row.count,
+            total_seconds: row.total.as_secs_f64(),
+            mean_seconds: row.mean.as_secs_f64(),
+            min_seconds: row.min.as_secs_f64(),
+            p50_seconds: row.p50.as_secs_f64(),
+            p90_seconds: row.p90.as_secs_f64(),
+            p99_seconds: row.p99.as_secs_f64(),
+            max_seconds: row.max.as_secs_f64(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<Record> for Row {
+    fn from(record: Record) -> Self {
+        Row {
+            name: record.metric,
+            struct_name: record.struct_name,
+            function: record.function,
+            labels: record.labels.into_iter().collect(),
+            count: record.count,
+            total: seconds(record.total_seconds),
+            mean: seconds(record.mean_seconds),
+            min: seconds(record.min_seconds),
+            p50: seconds(record.p50_seconds),
+            p90: seconds(record.p90_seconds),
+            p99: seconds(record.p99_seconds),
+            max: seconds(record.max_seconds),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Record {
+    /// CSV cells, in [CSV_HEADER] order.
+    fn to_cells(&self) -> [String; 12] {
+        let labels: Vec<String> = self
+            .labels
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect();
+        [
+            self.metric.clone(),
+            self.struct_name.clone().unwrap_or_default(),
+            self.function.clone().unwrap_or_default(),
+            labels.join(";"),
+            self.count.to_string(),
+            self.total_seconds.to_string(),
+            self.mean_seconds.to_string(),
+            self.min_seconds.to_string(),
+            self.p50_seconds.to_string(),
+            self.p90_seconds.to_string(),
+            self.p99_seconds.to_string(),
+            self.max_seconds.to_string(),
+        ]
+    }
+
+    /// Parse CSV cells, in [CSV_HEADER] order.
+    fn from_cells(cells: &[String]) -> Result<Self, String> {
+        if cells.len() != CSV_HEADER.len() {
+            return Err(format!(
+                "expected {} columns, found {}",
+                CSV_HEADER.len(),
+                cells.len()
+            ));
+        }
+        let number = |i: usize| {
+            cells[i]
+                .parse::<f64>()
+                .map_err(|e| format!("invalid {} `{}`: {e}", CSV_HEADER[i], cells[i]))
+        };
+        let optional = |i: usize| Some(cells[i].clone()).filter(|cell| !cell.is_empty());
+        let mut labels = BTreeMap::new();
+        for label in cells[3].split(';').filter(|label| !label.is_empty()) {
+            let (key, value) = label
+                .split_once('=')
+                .ok_or_else(|| format!("invalid label `{label}`"))?;
+            labels.insert(key.to_string(), value.to_string());
+        }
+
+        Ok(Record {
+            metric: cells[0].clone(),
+            struct_name: optional(1),
+            function: optional(2),
+            labels,
+            count: cells[4]
+                .parse()
+                .map_err(|e| format!("invalid count `{}`: {e}", cells[4]))?,
+            total_seconds: number(5)?,
+            mean_seconds: number(6)?,
+            min_seconds: number(7)?,
+            p50_seconds: number(8)?,
+            p90_seconds: number(9)?,
+            p99_seconds: number(10)?,
+            max_seconds: number(11)?,
+        })
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Report {
+    /// Write the report as a JSON array of rows.
+    pub fn write_json(&self, writer: impl Write) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    /// Read a report written by [Report::write_json].
+    pub fn read_json(reader: impl Read) -> std::io::Result<Self> {
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    /// Write the report as CSV, with a header line. Labels other than struct and function are
+    /// written in a single `labels` column, as `key=value` separated by `;`.
+    pub fn write_csv(&self, mut writer: impl Write) -> std::io::Result<()> {
+        writeln!(writer, "{}", CSV_HEADER.join(","))?;
+        for row in &self.rows {
+            let cells = Record::from(row.clone()).to_cells();
+            let cells: Vec<String> = cells.iter().map(|cell| csv_cell(cell)).collect();
+            writeln!(writer, "{}", cells.join(","))?;
+        }
+        Ok(())
+    }
+
+    /// Read a report written by [Report::write_csv].
+    pub fn read_csv(mut reader: impl Read) -> std::io::Result<Self> {
+        let invalid = |line: usize, message: String| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("line {line}: {message}"),
+            )
+        };
+
+        let mut text = String::new();
+        reader.read_to_string(&mut text)?;
+        let mut lines = parse_csv(&text).into_iter().enumerate();
+        match lines.next() {
+            Some((_, header)) if header == CSV_HEADER => {}
+            _ => return Err(invalid(1, "missing header".to_string())),
+        }
+
+        let mut rows = Vec::new();
+        for (i, cells) in lines {
+            let record = Record::from_cells(&cells).map_err(|message| invalid(i + 1, message))?;
+            rows.push(record.into());
+        }
+        Ok(Report { rows })
+    }
+}
+
+/// Quote a CSV cell if needed.
+#[cfg(feature = "serde")]
+fn csv_cell(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// Split CSV text in lines of cells, ignoring empty lines.
+#[cfg(feature = "serde")]
+fn parse_csv(text: &str) -> Vec<Vec<String>> {
+    let mut lines = Vec::new();
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                cell.push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => cells.push(std::mem::take(&mut cell)),
+            '\r' if !quoted => {}
+            '\n' if !quoted => {
+                cells.push(std::mem::take(&mut cell));
+                if cells != [""] {
+                    lines.push(std::mem::take(&mut cells));
+                }
+                cells.clear();
+            }
+            c => cell.push(c),
+        }
+    }
+    if !cell.is_empty() || !cells.is_empty() {
+        cells.push(cell);
+        lines.push(cells);
+    }
+    lines
+}
+
+/// Environment variable with the path of the file where the report is written on exit.
+pub const REPORT_FILE_ENV: &str = "FUNCTION_TIMER_REPORT";
+
+/// Install a [ReportRecorder] as the global recorder and print its report when the returned
+/// guard is dropped.
+///
+/// The report is written to stderr, or to the file named by the [REPORT_FILE_ENV]
+/// environment variable if set. It fails if a global recorder is already installed.
+pub fn install_report_on_exit() -> Result<ReportGuard, SetRecorderError<ReportRecorder>> {
+    let recorder = ReportRecorder::new();
+    recorder.install()?;
+    Ok(ReportGuard { recorder })
+}
+
+/// Guard printing the report when dropped, see [install_report_on_exit].
+pub struct ReportGuard {
+    recorder: ReportRecorder,
+}
+
+impl ReportGuard {
+    /// Recorder installed as the global recorder.
+    pub fn recorder(&self) -> &ReportRecorder {
+        &self.recorder
+    }
+}
+
+impl Drop for ReportGuard {
+    fn drop(&mut self) {
+        let report = self.recorder.report();
+        if let Some(path) = std::env::var_os(REPORT_FILE_ENV) {
+            match std::fs::write(&path, report.to_string()) {
+                Ok(()) => return,
+                Err(e) => eprintln!(
+                    "Unable to write function-timer report to {}: {e}",
+                    std::path::Path::new(&path).display()
+                ),
+            }
+        }
+        let _ = write!(std::io::stderr().lock(), "{report}");
+    }
+}
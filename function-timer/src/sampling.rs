@@ -0,0 +1,54 @@
+//! Sampling of timed calls.
+use std::cell::Cell;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::thread::LocalKey;
+
+thread_local! {
+    static RNG: Cell<u64> = Cell::new(seed());
+}
+
+/// Random non-zero seed for the thread's generator.
+fn seed() -> u64 {
+    RandomState::new().build_hasher().finish() | 1
+}
+
+/// Next random number in `[0, 1)`, using a thread-local xorshift generator.
+fn next_random() -> f64 {
+    RNG.with(|rng| {
+        let mut x = rng.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        rng.set(x);
+        (x >> 11) as f64 / (1u64 << 53) as f64
+    })
+}
+
+/// Select which calls are timed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Sampling {
+    /// Time calls with the given probability, between 0 and 1.
+    Rate(f64),
+    /// Time one call out of `n`, counted per thread.
+    Every(u64),
+}
+
+impl Sampling {
+    /// Whether the current call should be timed.
+    ///
+    /// # Parameters
+    ///
+    /// * `calls` : calls counter of the call site for the current thread. It is only
+    ///   used by [Sampling::Every].
+    pub fn sample(&self, calls: &'static LocalKey<Cell<u64>>) -> bool {
+        match *self {
+            Self::Rate(rate) => next_random() < rate,
+            Self::Every(n) => calls.with(|calls| {
+                let count = calls.get();
+                calls.set(count.wrapping_add(1));
+                count % n.max(1) == 0
+            }),
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! Stack of active timers on the current thread.
+use std::cell::{Cell, RefCell};
+use std::time::Duration;
+
+/// Value of the `caller` tag for functions not called by a timed function.
+pub const ROOT_CALLER: &str = "__root__";
+
+thread_local! {
+    static NEXT_ID: Cell<u64> = const { Cell::new(0) };
+    static STACK: RefCell<Vec<Frame>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Active timer.
+pub(crate) struct Frame {
+    pub(crate) id: u64,
+    pub(crate) metric_name: &'static str,
+    pub(crate) struct_name: Option<&'static str>,
+    pub(crate) function: &'static str,
+    /// Time spent in nested timers.
+    pub(crate) children: Duration,
+    /// Number of active recursive calls.
+    recursive_calls: usize,
+    /// Maximum recursion depth, `1` without recursion.
+    pub(crate) max_depth: usize,
+}
+
+impl Frame {
+    /// Function, as `Struct::function` for methods.
+    fn name(&self) -> String {
+        match self.struct_name {
+            Some(struct_name) => format!("{struct_name}::{}", self.function),
+            None => self.function.to_string(),
+        }
+    }
+}
+
+/// Push a timer on the stack, returning its id.
+pub(crate) fn push(
+    metric_name: &'static str,
+    struct_name: Option<&'static str>,
+    function: &'static str,
+) -> u64 {
+    let id = NEXT_ID.with(|next| {
+        let id = next.get();
+        next.set(id.wrapping_add(1));
+        id
+    });
+    STACK.with(|stack| {
+        stack.borrow_mut().push(Frame {
+            id,
+            metric_name,
+            struct_name,
+            function,
+            children: Duration::ZERO,
+            recursive_calls: 0,
+            max_depth: 1,
+        })
+    });
+    id
+}
+
+/// Up to `depth` enclosing timers, outermost first, separated by `/`, or [ROOT_CALLER].
+///
+/// The timer `id`, if already on the stack, is ignored.
+pub(crate) fn callers(depth: usize, id: Option<u64>) -> String {
+    STACK.with(|stack| {
+        let stack = stack.borrow();
+        let mut callers: Vec<String> = stack
+            .iter()
+            .rev()
+            .filter(|frame| Some(frame.id) != id)
+            .take(depth)
+            .map(Frame::name)
+            .collect();
+        if callers.is_empty() {
+            return ROOT_CALLER.to_string();
+        }
+        callers.reverse();
+        callers.join("/")
+    })
+}
+
+/// Timers from the bottom of the stack up to the timer `id`, separated by `;`.
+pub(crate) fn path(id: u64) -> Option<String> {
+    STACK.with(|stack| {
+        let stack = stack.borrow();
+        let index = stack.iter().rposition(|frame| frame.id == id)?;
+        let names: Vec<String> = stack[..=index].iter().map(Frame::name).collect();
+        Some(names.join(";"))
+    })
+}
+
+/// Register a recursive call, returning the id of the outermost timer of the same metric and
+/// function, if any.
+pub(crate) fn recurse(
+    metric_name: &'static str,
+    struct_name: Option<&'static str>,
+    function: &'static str,
+) -> Option<u64> {
+    STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        let frame = stack.iter_mut().find(|frame| {
+            frame.metric_name == metric_name
+                && frame.struct_name == struct_name
+                && frame.function == function
+        })?;
+        frame.recursive_calls += 1;
+        frame.max_depth = frame.max_depth.max(frame.recursive_calls + 1);
+        Some(frame.id)
+    })
+}
+
+/// End a recursive call registered with [recurse].
+pub(crate) fn unwind(id: u64) {
+    STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        if let Some(frame) = stack.iter_mut().rev().find(|frame| frame.id == id) {
+            frame.recursive_calls = frame.recursive_calls.saturating_sub(1);
+        }
+    })
+}
+
+/// Remove a timer from the stack and add its execution time to its parent.
+///
+/// The timer is usually on top, but it is looked up in the whole stack in case timers
+/// aren't dropped in order.
+pub(crate) fn pop(id: u64, elapsed: Duration) -> Option<Frame> {
+    STACK.with(|stack| {
+        let mut stack = stack.borrow_mut();
+        let index = stack.iter().rposition(|frame| frame.id == id)?;
+        let frame = stack.remove(index);
+        if let Some(parent) = index.checked_sub(1).and_then(|i| stack.get_mut(i)) {
+            parent.children += elapsed;
+        }
+        Some(frame)
+    })
+}
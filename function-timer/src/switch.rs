@@ -0,0 +1,49 @@
+//! Runtime switches to enable or disable timing.
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::global::Setting;
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+static DISABLED_METRICS: Setting<BTreeSet<String>> = Setting::new(BTreeSet::new());
+
+/// Enable or disable all timers. Timers are enabled by default.
+///
+/// Disabled timers don't read the clock nor emit any metric.
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether timers are globally enabled.
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Enable or disable timers of a metric. Metrics are enabled by default.
+///
+/// # Parameters
+///
+/// * `metric_name` : name of the metric.
+/// * `enabled` : `false` to disable timers of this metric.
+pub fn set_metric_enabled(metric_name: &str, enabled: bool) {
+    DISABLED_METRICS.update(|disabled| {
+        if enabled {
+            disabled.remove(metric_name);
+        } else {
+            disabled.insert(metric_name.to_string());
+        }
+        !disabled.is_empty()
+    });
+}
+
+/// Whether timers of a metric are enabled, taking the global switch into account.
+pub fn is_metric_enabled(metric_name: &str) -> bool {
+    if !is_enabled() {
+        return false;
+    }
+    if !DISABLED_METRICS.is_custom() {
+        return true;
+    }
+
+    DISABLED_METRICS.read(|disabled| !disabled.contains(metric_name))
+}
@@ -0,0 +1,324 @@
+//! Utilities to test timed code, available with the `test-util` feature.
+//!
+//! [MockClock] makes recorded durations exact, [capture] collects metrics recorded by a
+//! closure and [assert_timed] checks that a function was timed.
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use metrics::{
+    Counter, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+};
+
+use crate::clock::{self, Clock};
+
+thread_local! {
+    static LOCAL_MOCK: Cell<Option<&'static MockClock>> = const { Cell::new(None) };
+    static CAPTURING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether [capture] is running on the current thread, so that timers don't use handles cached
+/// for another recorder.
+pub(crate) fn is_capturing() -> bool {
+    CAPTURING.with(Cell::get)
+}
+
+/// Clock that only moves when advanced, so that recorded durations are exact.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use function_timer::testing::{self, MockClock};
+/// use function_timer::Clock;
+/// use function_timer::time;
+///
+/// #[time("my_metric")]
+/// fn my_function() {
+///     testing::advance(Duration::from_millis(250));
+/// }
+///
+/// static CLOCK: MockClock = MockClock::new();
+/// let _guard = CLOCK.install();
+/// my_function();
+/// assert_eq!(CLOCK.now(), Duration::from_millis(250));
+/// ```
+#[derive(Debug, Default)]
+pub struct MockClock {
+    nanos: AtomicU64,
+}
+
+impl MockClock {
+    /// Create a new clock, at time zero.
+    pub const fn new() -> Self {
+        Self {
+            nanos: AtomicU64::new(0),
+        }
+    }
+
+    /// Move the clock forward.
+    pub fn advance(&self, duration: Duration) {
+        self.nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Set the current time of the clock.
+    pub fn set(&self, now: Duration) {
+        self.nanos.store(now.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Use this clock for timers on the current thread until the guard is dropped.
+    pub fn install(&'static self) -> MockClockGuard {
+        let previous_clock = clock::set_local_clock(Some(self));
+        let previous_mock = LOCAL_MOCK.with(|local| local.replace(Some(self)));
+        MockClockGuard {
+            previous_clock,
+            previous_mock,
+        }
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Duration {
+        Duration::from_nanos(self.nanos.load(Ordering::Relaxed))
+    }
+}
+
+/// Restore the previous clock of the thread when dropped.
+#[must_use = "the mock clock is uninstalled when the guard is dropped"]
+pub struct MockClockGuard {
+    previous_clock: Option<&'static dyn Clock>,
+    previous_mock: Option<&'static MockClock>,
+}
+
+impl Drop for MockClockGuard {
+    fn drop(&mut self) {
+        clock::set_local_clock(self.previous_clock);
+        LOCAL_MOCK.with(|local| local.set(self.previous_mock));
+    }
+}
+
+/// Advance the [MockClock] installed on the current thread, if any.
+///
+/// Timed functions can call it in place of a sleep.
+pub fn advance(duration: Duration) {
+    if let Some(clock) = LOCAL_MOCK.with(|local| local.get()) {
+        clock.advance(duration);
+    }
+}
+
+/// Name and sorted labels of a metric.
+type MetricKey = (String, Vec<(String, String)>);
+
+/// Metrics recorded by [capture], with labels sorted by key.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Samples {
+    histograms: BTreeMap<MetricKey, Vec<f64>>,
+    counters: BTreeMap<MetricKey, u64>,
+}
+
+/// Name and sorted labels of a metric.
+fn metric_key(name: &str, labels: &[(&str, &str)]) -> MetricKey {
+    let mut labels: Vec<(String, String)> = labels
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    labels.sort();
+    (name.to_string(), labels)
+}
+
+impl Samples {
+    /// Values of the histogram with exactly these labels, in seconds for timers.
+    pub fn histogram(&self, name: &str, labels: &[(&str, &str)]) -> Option<&[f64]> {
+        self.histograms
+            .get(&metric_key(name, labels))
+            .map(Vec::as_slice)
+    }
+
+    /// Value of the counter with exactly these labels.
+    pub fn counter(&self, name: &str, labels: &[(&str, &str)]) -> Option<u64> {
+        self.counters.get(&metric_key(name, labels)).copied()
+    }
+
+    /// All histograms, as name, sorted labels and values.
+    pub fn histograms(&self) -> impl Iterator<Item = (&str, &[(String, String)], &[f64])> {
+        self.histograms
+            .iter()
+            .map(|((name, labels), values)| (name.as_str(), labels.as_slice(), values.as_slice()))
+    }
+
+    /// All counters, as name, sorted labels and value.
+    pub fn counters(&self) -> impl Iterator<Item = (&str, &[(String, String)], u64)> {
+        self.counters
+            .iter()
+            .map(|((name, labels), value)| (name.as_str(), labels.as_slice(), *value))
+    }
+
+    /// Implementation of [assert_timed].
+    #[doc(hidden)]
+    #[track_caller]
+    pub fn assert_timed(&self, name: &str, labels: &[(&str, &str)]) -> &[f64] {
+        match self.histogram(name, labels) {
+            Some(values) => values,
+            None => {
+                let recorded: Vec<String> = self
+                    .histograms
+                    .keys()
+                    .map(|(name, labels)| format!("{name} {labels:?}"))
+                    .collect();
+                panic!(
+                    "No histogram `{name}` with labels {:?}, recorded histograms : [{}]",
+                    metric_key(name, labels).1,
+                    recorded.join(", ")
+                )
+            }
+        }
+    }
+}
+
+/// Values recorded by one histogram.
+#[derive(Default)]
+struct HistogramValues(Mutex<Vec<f64>>);
+
+impl HistogramValues {
+    /// Copy of recorded values.
+    fn to_vec(&self) -> Vec<f64> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone()
+    }
+}
+
+impl HistogramFn for HistogramValues {
+    fn record(&self, value: f64) {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(value);
+    }
+}
+
+/// Recorder of [capture].
+#[derive(Default)]
+struct CaptureRecorder {
+    histograms: Mutex<BTreeMap<Key, Arc<HistogramValues>>>,
+    counters: Mutex<BTreeMap<Key, Arc<AtomicU64>>>,
+}
+
+/// Name and sorted labels of a key.
+fn key_parts(key: &Key) -> MetricKey {
+    let labels: Vec<(&str, &str)> = key
+        .labels()
+        .map(|label| (label.key(), label.value()))
+        .collect();
+    metric_key(key.name(), &labels)
+}
+
+impl Recorder for CaptureRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        let mut counters = self
+            .counters
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        Counter::from_arc(counters.entry(key.clone()).or_default().clone())
+    }
+
+    fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::noop()
+    }
+
+    fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+        let mut histograms = self
+            .histograms
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        Histogram::from_arc(histograms.entry(key.clone()).or_default().clone())
+    }
+}
+
+/// Run `f` with a recorder local to the current thread, and return histograms and counters it
+/// recorded. Gauges are ignored.
+///
+/// # Example
+///
+/// ```rust
+/// use std::time::Duration;
+/// use function_timer::testing::{self, MockClock};
+/// use function_timer::{assert_timed, time};
+///
+/// struct Test {}
+///
+/// #[time("my_metric")]
+/// impl Test {
+///     pub fn test(&self) {
+///         testing::advance(Duration::from_secs(2));
+///     }
+/// }
+///
+/// static CLOCK: MockClock = MockClock::new();
+/// let _guard = CLOCK.install();
+/// let samples = testing::capture(|| Test {}.test());
+///
+/// let values = assert_timed!(samples, "my_metric", struct = "Test", function = "test");
+/// assert_eq!(values, [2.0]);
+/// ```
+pub fn capture(f: impl FnOnce()) -> Samples {
+    struct Capturing(bool);
+
+    impl Drop for Capturing {
+        fn drop(&mut self) {
+            CAPTURING.with(|capturing| capturing.set(self.0));
+        }
+    }
+
+    let recorder = CaptureRecorder::default();
+    let _capturing = Capturing(CAPTURING.with(|capturing| capturing.replace(true)));
+    metrics::with_local_recorder(&recorder, f);
+
+    let histograms = recorder
+        .histograms
+        .into_inner()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let counters = recorder
+        .counters
+        .into_inner()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    Samples {
+        histograms: histograms
+            .iter()
+            .map(|(key, values)| (key_parts(key), values.to_vec()))
+            .collect(),
+        counters: counters
+            .iter()
+            .map(|(key, value)| (key_parts(key), value.load(Ordering::Relaxed)))
+            .collect(),
+    }
+}
+
+/// Assert that [Samples] contain a histogram with the given name and exactly the given labels,
+/// and return its values.
+///
+/// ```rust,ignore
+/// let values = assert_timed!(samples, "my_metric", struct = "Test", function = "test");
+/// ```
+#[macro_export]
+macro_rules! assert_timed {
+    ($samples:expr, $name:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::testing::Samples::assert_timed(
+            &$samples,
+            $name,
+            &[$((stringify!($key), $value)),*],
+        )
+    };
+}
+
+pub use crate::assert_timed;
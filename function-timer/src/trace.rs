@@ -0,0 +1,187 @@
+//! Capture of timed calls in the [Trace Event Format], to open a timeline in
+//! `chrome://tracing` or [Perfetto](https://ui.perfetto.dev).
+//!
+//! ```
+//! use function_timer::time;
+//!
+//! #[time("my_metric")]
+//! fn work() {}
+//!
+//! function_timer::trace::start();
+//! work();
+//! let trace = function_timer::trace::stop();
+//!
+//! assert_eq!(trace.events().len(), 1);
+//! let json = trace.to_json();
+//! ```
+//!
+//! While capturing, each timer that records a value also stores its begin timestamp, duration,
+//! thread and labels in memory, whatever the recorder. Timers of async functions are
+//! attributed to the thread where they end.
+//!
+//! [Trace Event Format]: https://docs.google.com/document/d/1CvAClvFfyA5R-PhYUmn5OOQtYMH4h6I0nSsKchNAySU
+use std::fmt::Write as _;
+use std::io::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use metrics::Label;
+
+use crate::global::Capture;
+
+static EVENTS: Capture<Vec<TraceEvent>> = Capture::new(Vec::new());
+static NEXT_THREAD_ID: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    static THREAD_ID: u64 = NEXT_THREAD_ID.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Timed call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TraceEvent {
+    /// Function, as `Struct::function` for methods.
+    pub name: String,
+    /// Metric name.
+    pub metric: String,
+    /// Start of the call, since the origin of the clock.
+    pub begin: Duration,
+    /// Execution time.
+    pub duration: Duration,
+    /// Thread, numbered from 1 in order of first timed call.
+    pub thread_id: u64,
+    /// Thread name, if any.
+    pub thread_name: Option<String>,
+    /// Labels, as `(key, value)`.
+    pub labels: Vec<(String, String)>,
+}
+
+/// Start capturing timed calls, discarding previously captured ones.
+pub fn start() {
+    EVENTS.start();
+}
+
+/// Stop capturing and return captured calls.
+pub fn stop() -> Trace {
+    Trace {
+        events: EVENTS.stop(),
+    }
+}
+
+/// Whether calls are being captured.
+pub fn is_capturing() -> bool {
+    EVENTS.is_capturing()
+}
+
+/// Store a timed call if capturing.
+pub(crate) fn record(
+    metric: &str,
+    struct_name: Option<&str>,
+    function: &str,
+    begin: Duration,
+    duration: Duration,
+    labels: Vec<Label>,
+) {
+    let name = match struct_name {
+        Some(struct_name) => format!("{struct_name}::{function}"),
+        None => function.to_string(),
+    };
+    let event = TraceEvent {
+        name,
+        metric: metric.to_string(),
+        begin,
+        duration,
+        thread_id: THREAD_ID.with(|id| *id),
+        thread_name: std::thread::current().name().map(str::to_string),
+        labels: labels
+            .iter()
+            .map(|label| (label.key().to_string(), label.value().to_string()))
+            .collect(),
+    };
+    EVENTS.record(|events| events.push(event));
+}
+
+/// Captured calls, in order of completion.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trace {
+    events: Vec<TraceEvent>,
+}
+
+impl Trace {
+    /// Captured calls.
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    /// Write the trace as a JSON array of complete (`X`) events, preceded by thread name
+    /// metadata (`M`) events. Timestamps are in microseconds.
+    pub fn write_json(&self, mut writer: impl Write) -> std::io::Result<()> {
+        writer.write_all(self.to_json().as_bytes())
+    }
+
+    /// Trace as JSON, see [Trace::write_json].
+    pub fn to_json(&self) -> String {
+        let pid = std::process::id();
+        let mut entries = Vec::with_capacity(self.events.len());
+
+        let mut named_threads = Vec::new();
+        for event in &self.events {
+            if let Some(thread_name) = &event.thread_name {
+                if !named_threads.contains(&event.thread_id) {
+                    named_threads.push(event.thread_id);
+                    entries.push(format!(
+                        r#"{{"name":"thread_name","ph":"M","pid":{pid},"tid":{},"args":{{"name":{}}}}}"#,
+                        event.thread_id,
+                        json_string(thread_name)
+                    ));
+                }
+            }
+        }
+
+        for event in &self.events {
+            let mut args = String::new();
+            for (i, (key, value)) in event.labels.iter().enumerate() {
+                if i > 0 {
+                    args.push(',');
+                }
+                let _ = write!(args, "{}:{}", json_string(key), json_string(value));
+            }
+            entries.push(format!(
+                r#"{{"name":{},"cat":{},"ph":"X","ts":{},"dur":{},"pid":{pid},"tid":{},"args":{{{args}}}}}"#,
+                json_string(&event.name),
+                json_string(&event.metric),
+                micros(event.begin),
+                micros(event.duration),
+                event.thread_id,
+            ));
+        }
+
+        format!("[{}]", entries.join(",\n"))
+    }
+}
+
+/// Duration in microseconds, with nanosecond precision.
+fn micros(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    format!("{}.{:03}", nanos / 1000, nanos % 1000)
+}
+
+/// Quoted and escaped JSON string.
+fn json_string(value: &str) -> String {
+    let mut json = String::with_capacity(value.len() + 2);
+    json.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
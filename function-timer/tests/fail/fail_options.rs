@@ -0,0 +1,48 @@
+use function_timer::time;
+
+#[time("my_metric", slow = "250 lightyears")]
+fn test() {}
+
+#[time("my_metric", unknown = true)]
+fn test2() {}
+
+#[time("my_metric", slo = ["100ms", "fast"])]
+fn test3() {}
+
+#[time("my_metric", sample = 1.5)]
+fn test4() {}
+
+#[time("my_metric", every = 0)]
+fn test5() {}
+
+#[time("my_metric", every = 10, sample = 0.1)]
+fn test6() {}
+
+#[time("my_metric", clock = "process")]
+fn test7() {}
+
+#[time("my_metric", caller = 0)]
+fn test8() {}
+
+#[time("my_metric", recursion = "innermost")]
+fn test9() {}
+
+#[time("my_metric", self_time = true)]
+async fn test10() {}
+
+#[time("my_metric", recursion = "outermost")]
+async fn test11() {}
+
+#[time("my_metric", recursion_depth = true)]
+async fn test12() {}
+
+struct Test {}
+
+#[time("my_metric", self_time = true)]
+impl Test {
+    pub async fn first(&self) {}
+
+    pub async fn second(&self) {}
+}
+
+fn main() {}
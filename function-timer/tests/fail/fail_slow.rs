@@ -0,0 +1,9 @@
+use function_timer::time;
+
+#[time("my_metric", slow = "250ms")]
+fn test() {}
+
+#[time("my_metric", slow = "250ms", slow_counter = true)]
+fn test2() {}
+
+fn main() {}
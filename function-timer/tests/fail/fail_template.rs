@@ -0,0 +1,19 @@
+use function_timer::time;
+
+struct Test {}
+
+#[time("{struct}_{function}")]
+fn test() {}
+
+#[time("{unknown}")]
+fn test2() {}
+
+#[time("{trait}_{function}")]
+impl Test {
+    fn test3() {}
+}
+
+#[time("{function")]
+fn test4() {}
+
+fn main() {}
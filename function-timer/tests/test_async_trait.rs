@@ -0,0 +1,102 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use function_timer::testing::{self, MockClock, Samples};
+use function_timer::{assert_timed, time};
+
+#[async_trait]
+trait MyTrait {
+    async fn trait_function(&self);
+}
+
+struct TimeFirst {}
+
+#[time("time_first_metric")]
+#[async_trait]
+impl MyTrait for TimeFirst {
+    async fn trait_function(&self) {
+        testing::advance(Duration::from_millis(200));
+    }
+}
+
+struct AsyncTraitFirst {}
+
+#[async_trait]
+#[time("async_trait_first_metric")]
+impl MyTrait for AsyncTraitFirst {
+    async fn trait_function(&self) {
+        testing::advance(Duration::from_millis(200));
+    }
+}
+
+struct MethodAttribute {}
+
+#[async_trait]
+impl MyTrait for MethodAttribute {
+    #[time("method_metric")]
+    async fn trait_function(&self) {
+        testing::advance(Duration::from_millis(200));
+    }
+}
+
+#[test]
+fn test_time_before_async_trait() {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
+    let samples = testing::capture(|| {
+        let t = TimeFirst {};
+        futures_executor::block_on(t.trait_function());
+    });
+
+    assert_eq!(samples.histograms().count(), 1);
+    let values = assert_timed!(
+        samples,
+        "time_first_metric",
+        struct = "TimeFirst",
+        function = "trait_function"
+    );
+    assert_eq!(values, [0.2]);
+}
+
+#[test]
+fn test_time_after_async_trait() {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
+    let samples = testing::capture(|| {
+        let t = AsyncTraitFirst {};
+        futures_executor::block_on(t.trait_function());
+    });
+
+    assert_eq!(samples.histograms().count(), 1);
+    let values = assert_timed!(
+        samples,
+        "async_trait_first_metric",
+        struct = "AsyncTraitFirst",
+        function = "trait_function"
+    );
+    assert_eq!(values, [0.2]);
+}
+
+#[test]
+fn test_time_method_in_async_trait() {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
+    let samples = testing::capture(|| {
+        let t = MethodAttribute {};
+        futures_executor::block_on(t.trait_function());
+    });
+
+    assert_eq!(samples.histograms().count(), 1);
+    let values = assert_timed!(samples, "method_metric", function = "trait_function");
+    assert_eq!(values, [0.2]);
+}
+
+#[test]
+fn test_future_creation_not_timed() {
+    static T: AsyncTraitFirst = AsyncTraitFirst {};
+    let mut future = None;
+    let samples = testing::capture(|| future = Some(T.trait_function()));
+    drop(future);
+
+    assert_eq!(samples, Samples::default());
+}
@@ -0,0 +1,19 @@
+use function_timer::{assert_timed, testing, time};
+
+#[time("parent")]
+fn parent() {
+    child();
+}
+
+#[time("child", caller = true)]
+fn child() {}
+
+#[test]
+fn test_first_call_has_caller() {
+    // The parent started before any timer with `caller` ran
+    let samples = testing::capture(parent);
+
+    assert_eq!(samples.histograms().count(), 2);
+    let values = assert_timed!(samples, "child", caller = "parent", function = "child");
+    assert_eq!(values.len(), 1);
+}
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use function_timer::testing::{self, MockClock};
+use function_timer::{assert_timed, time};
+
+static CLOCK: MockClock = MockClock::new();
+
+#[time("my_metric")]
+pub fn free_function() {
+    CLOCK.advance(Duration::from_millis(1500));
+}
+
+#[time("outer_metric")]
+pub fn set_clock_function() {
+    function_timer::set_clock(&CLOCK).unwrap();
+    free_function();
+}
+
+#[test]
+fn test_global_clock() {
+    let samples = testing::capture(|| {
+        set_clock_function();
+        free_function();
+    });
+    assert_eq!(
+        function_timer::set_clock(&function_timer::InstantClock),
+        Err(function_timer::SetClockError)
+    );
+
+    assert_eq!(samples.histograms().count(), 2);
+    let values = assert_timed!(samples, "my_metric", function = "free_function");
+    assert_eq!(values, [1.5, 1.5]);
+    // Started before the clock was set, so it ends with the default clock
+    let values = assert_timed!(samples, "outer_metric", function = "set_clock_function");
+    assert!(values[0] < 1.0);
+}
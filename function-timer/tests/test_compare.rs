@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use function_timer::report::{Report, ReportRecorder};
+use function_timer::testing::{self, MockClock};
+use function_timer::{compare, time, Regression};
+
+#[time("my_metric")]
+fn stable(millis: u64) {
+    testing::advance(Duration::from_millis(millis));
+}
+
+#[time("my_metric")]
+fn regressed(millis: u64) {
+    testing::advance(Duration::from_millis(millis));
+}
+
+#[time("my_metric")]
+fn removed() {}
+
+/// Report of `stable` and `regressed` timed with the given durations.
+fn report(stable_millis: &[u64], regressed_millis: &[u64]) -> Report {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
+
+    let recorder = ReportRecorder::new();
+    metrics::with_local_recorder(&recorder, || {
+        stable_millis.iter().for_each(|millis| stable(*millis));
+        regressed_millis
+            .iter()
+            .for_each(|millis| regressed(*millis));
+        if stable_millis.len() == 1 {
+            removed();
+        }
+    });
+    recorder.report()
+}
+
+#[test]
+fn test_no_regression() {
+    let baseline = report(&[100], &[100, 100]);
+    let current = report(&[105, 105], &[80, 110]);
+
+    let comparison = compare(&baseline, &current, 0.1);
+    assert!(comparison.is_ok());
+    assert_eq!(comparison.to_string(), "");
+}
+
+#[test]
+fn test_regression() {
+    let baseline = report(&[100], &[100, 100]);
+    let current = report(&[100, 100], &[100, 150]);
+
+    let comparison = compare(&baseline, &current, 0.1);
+    assert!(!comparison.is_ok());
+    assert_eq!(
+        comparison.regressions(),
+        &[Regression {
+            name: "my_metric".to_string(),
+            struct_name: None,
+            function: Some("regressed".to_string()),
+            labels: Vec::new(),
+            percentile: "p99",
+            baseline: Duration::from_millis(100),
+            current: Duration::from_millis(150),
+        }]
+    );
+    assert_eq!(
+        comparison.to_string(),
+        "my_metric{function=regressed} p99 : 100.00ms -> 150.00ms (+50.0%)\n"
+    );
+
+    assert!(compare(&baseline, &current, 0.5).is_ok());
+}
+
+#[test]
+fn test_median_regression() {
+    let baseline = report(&[100], &[]);
+    let current = report(&[200, 200], &[]);
+
+    let percentiles: Vec<&str> = compare(&baseline, &current, 0.0)
+        .regressions()
+        .iter()
+        .map(|regression| regression.percentile)
+        .collect();
+    assert_eq!(percentiles, ["p50", "p99"]);
+}
@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+
+use function_timer::report::{Report, ReportRecorder};
+use function_timer::testing::{self, MockClock};
+use function_timer::time;
+
+#[time("my_metric")]
+fn work(millis: u64) {
+    testing::advance(Duration::from_millis(millis));
+}
+
+fn report(millis: u64) -> Report {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
+
+    let recorder = ReportRecorder::new();
+    metrics::with_local_recorder(&recorder, || work(millis));
+    recorder.report()
+}
+
+fn write(name: &str, report: &Report) -> PathBuf {
+    let path = std::env::temp_dir().join(format!(
+        "function_timer_compare_{}_{name}",
+        std::process::id()
+    ));
+    let file = std::fs::File::create(&path).unwrap();
+    if name.ends_with(".csv") {
+        report.write_csv(file).unwrap();
+    } else {
+        report.write_json(file).unwrap();
+    }
+    path
+}
+
+fn compare(args: &[&std::ffi::OsStr]) -> (Option<i32>, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_function-timer-compare"))
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.code(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+#[test]
+fn test_compare_bin() {
+    let baseline = write("baseline.json", &report(100));
+    let current = write("current.csv", &report(120));
+
+    let (code, stdout) = compare(&[baseline.as_os_str(), current.as_os_str()]);
+    assert_eq!(code, Some(1));
+    assert_eq!(
+        stdout,
+        "my_metric{function=work} p50 : 100.00ms -> 120.00ms (+20.0%)
+my_metric{function=work} p99 : 100.00ms -> 120.00ms (+20.0%)
+"
+    );
+
+    let (code, stdout) = compare(&[
+        baseline.as_os_str(),
+        current.as_os_str(),
+        "--tolerance".as_ref(),
+        "0.25".as_ref(),
+    ]);
+    assert_eq!(code, Some(0));
+    assert_eq!(stdout, "");
+
+    let (code, _) = compare(&[baseline.as_os_str()]);
+    assert_eq!(code, Some(2));
+
+    std::fs::remove_file(baseline).unwrap();
+    std::fs::remove_file(current).unwrap();
+}
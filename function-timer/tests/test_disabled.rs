@@ -0,0 +1,34 @@
+//! Run with `FUNCTION_TIMER_DISABLED=1` or `RUSTFLAGS="--cfg function_timer_disabled"`.
+#![cfg(function_timer_disabled)]
+
+use function_timer::testing::{self, Samples};
+use function_timer::time;
+
+struct Test {}
+
+#[time("my_metric")]
+impl Test {
+    pub fn impl_function(&self) -> usize {
+        1
+    }
+
+    #[time(disable)]
+    pub fn disable(&self) {}
+}
+
+#[time("my_metric", slo = "1ms", every = 10)]
+pub fn free_function() -> usize {
+    2
+}
+
+#[test]
+fn test_disabled_feature() {
+    let samples = testing::capture(|| {
+        let t = Test {};
+        assert_eq!(t.impl_function(), 1);
+        t.disable();
+        assert_eq!(free_function(), 2);
+    });
+
+    assert_eq!(samples, Samples::default());
+}
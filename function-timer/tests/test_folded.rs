@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use function_timer::folded;
+use function_timer::testing::{self, MockClock};
+use function_timer::time;
+
+struct Server {}
+
+#[time("server")]
+impl Server {
+    pub fn handle(&self) {
+        testing::advance(Duration::from_micros(100));
+        db_query();
+        db_query();
+        render();
+    }
+}
+
+#[time("db")]
+fn db_query() {
+    testing::advance(Duration::from_micros(1234));
+}
+
+#[time("render")]
+fn render() {
+    testing::advance(Duration::from_micros(50));
+    db_query();
+}
+
+#[test]
+fn test_folded_stacks() {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
+
+    db_query();
+    folded::start();
+    Server {}.handle();
+    Server {}.handle();
+    let stacks = folded::stop();
+    db_query();
+
+    let expected = "Server::handle 200
+Server::handle;db_query 4936
+Server::handle;render 100
+Server::handle;render;db_query 2468
+";
+    assert_eq!(stacks.to_string(), expected);
+    assert_eq!(
+        stacks.stacks()["Server::handle;db_query"],
+        Duration::from_micros(4936)
+    );
+
+    let mut written = Vec::new();
+    stacks.write(&mut written).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), expected);
+}
@@ -0,0 +1,190 @@
+use std::time::Duration;
+
+use function_timer::report::{ReportRecorder, Row};
+use function_timer::testing::{self, MockClock};
+use function_timer::time;
+
+struct Test {}
+
+#[time("my_metric")]
+impl Test {
+    pub fn fast(&self) {
+        testing::advance(Duration::from_millis(10));
+    }
+
+    pub fn slow(&self, millis: u64) {
+        testing::advance(Duration::from_millis(millis));
+    }
+
+    pub fn slow_micros(&self, micros: u64) {
+        testing::advance(Duration::from_micros(micros));
+    }
+}
+
+#[time("my_metric", count = true)]
+fn counted() {
+    testing::advance(Duration::from_millis(1));
+}
+
+struct Overridden {}
+
+#[time("overridden", struct_label = "type", function_label = "method")]
+impl Overridden {
+    pub fn call(&self) {}
+}
+
+#[time("recursive", recursion = "outermost", recursion_depth = true)]
+fn recursive(depth: u64) {
+    testing::advance(Duration::from_millis(1));
+    if depth > 1 {
+        recursive(depth - 1);
+    }
+}
+
+/// Percentiles are estimated within 2%.
+fn assert_percentile(actual: Duration, expected: Duration) {
+    let error = (actual.as_secs_f64() / expected.as_secs_f64() - 1.0).abs();
+    assert!(error <= 0.02, "{actual:?} is not close to {expected:?}");
+}
+
+#[test]
+fn test_report() {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
+
+    let recorder = ReportRecorder::new();
+    let test = Test {};
+    metrics::with_local_recorder(&recorder, || {
+        test.fast();
+        for millis in 1..=100 {
+            test.slow(millis);
+        }
+        counted();
+    });
+
+    let report = recorder.report();
+    let ms = Duration::from_millis;
+    let mut rows = report.rows().to_vec();
+    let slow = &mut rows[0];
+    for (actual, expected) in [
+        (&mut slow.p50, ms(50)),
+        (&mut slow.p90, ms(90)),
+        (&mut slow.p99, ms(99)),
+    ] {
+        assert_percentile(*actual, expected);
+        *actual = expected;
+    }
+    assert_eq!(
+        rows,
+        [
+            Row {
+                name: "my_metric".to_string(),
+                struct_name: Some("Test".to_string()),
+                function: Some("slow".to_string()),
+                labels: Vec::new(),
+                count: 100,
+                total: ms(5050),
+                mean: Duration::from_micros(50500),
+                min: ms(1),
+                p50: ms(50),
+                p90: ms(90),
+                p99: ms(99),
+                max: ms(100),
+            },
+            Row {
+                name: "my_metric".to_string(),
+                struct_name: Some("Test".to_string()),
+                function: Some("fast".to_string()),
+                labels: Vec::new(),
+                count: 1,
+                total: ms(10),
+                mean: ms(10),
+                min: ms(10),
+                p50: ms(10),
+                p90: ms(10),
+                p99: ms(10),
+                max: ms(10),
+            },
+            Row {
+                name: "my_metric".to_string(),
+                struct_name: None,
+                function: Some("counted".to_string()),
+                labels: Vec::new(),
+                count: 1,
+                total: ms(1),
+                mean: ms(1),
+                min: ms(1),
+                p50: ms(1),
+                p90: ms(1),
+                p99: ms(1),
+                max: ms(1),
+            },
+        ]
+    );
+
+    let table = report.top(2).to_string();
+    let lines: Vec<&str> = table.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("metric     struct  function  labels  count"));
+    assert!(lines[1].starts_with("my_metric  Test    slow                100"));
+    assert!(lines[1].ends_with("100.00ms"));
+    assert!(lines[2].contains("fast"));
+}
+
+#[test]
+fn test_percentiles() {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
+
+    let recorder = ReportRecorder::new();
+    metrics::with_local_recorder(&recorder, || {
+        for micros in 1..=100_000 {
+            Test {}.slow_micros(micros);
+        }
+    });
+
+    let report = recorder.report();
+    let row = &report.rows()[0];
+    let us = Duration::from_micros;
+    assert_eq!(row.count, 100_000);
+    assert_eq!(row.total, us(5_000_050_000));
+    assert_eq!(row.min, us(1));
+    assert_eq!(row.max, us(100_000));
+    assert_percentile(row.p50, us(50_000));
+    assert_percentile(row.p90, us(90_000));
+    assert_percentile(row.p99, us(99_000));
+}
+
+#[test]
+fn test_label_overrides() {
+    let recorder = ReportRecorder::new();
+    metrics::with_local_recorder(&recorder, || Overridden {}.call());
+
+    let report = recorder.report();
+    let row = &report.rows()[0];
+    assert_eq!(row.struct_name.as_deref(), Some("Overridden"));
+    assert_eq!(row.function.as_deref(), Some("call"));
+    assert!(row.labels.is_empty());
+}
+
+#[test]
+fn test_not_durations() {
+    let recorder = ReportRecorder::new();
+    metrics::with_local_recorder(&recorder, || recursive(50));
+
+    let report = recorder.report();
+    let names: Vec<&str> = report.rows().iter().map(|row| row.name.as_str()).collect();
+    assert_eq!(names, ["recursive"]);
+}
+
+#[test]
+fn test_clear() {
+    let recorder = ReportRecorder::new();
+    metrics::with_local_recorder(&recorder, || {
+        Test {}.fast();
+    });
+    assert_eq!(recorder.report().rows().len(), 1);
+
+    recorder.clear();
+    assert!(recorder.report().rows().is_empty());
+}
@@ -0,0 +1,117 @@
+use std::time::Duration;
+
+use function_timer::report::{Report, ReportRecorder, Row};
+use function_timer::testing::{self, MockClock};
+use function_timer::time;
+
+struct Test {}
+
+#[time("my_metric")]
+impl Test {
+    pub fn method(&self, millis: u64) {
+        testing::advance(Duration::from_millis(millis));
+        callee();
+    }
+}
+
+#[time("callee", caller = true)]
+fn callee() {
+    testing::advance(Duration::from_millis(5));
+}
+
+fn report() -> Report {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
+
+    let recorder = ReportRecorder::new();
+    metrics::with_local_recorder(&recorder, || {
+        for millis in [10, 20, 30, 40] {
+            Test {}.method(millis);
+        }
+        callee();
+    });
+    recorder.report()
+}
+
+#[test]
+fn test_json() {
+    let report = report();
+
+    let mut json = Vec::new();
+    report.write_json(&mut json).unwrap();
+    let json = String::from_utf8(json).unwrap();
+    assert!(json.contains(r#""metric": "my_metric""#));
+    assert!(json.contains(r#""struct": "Test""#));
+    assert!(json.contains(r#""caller": "Test::method""#));
+    assert!(json.contains(r#""total_seconds": 0.12"#));
+
+    assert_eq!(Report::read_json(json.as_bytes()).unwrap(), report);
+}
+
+#[test]
+fn test_csv() {
+    let report = report();
+
+    let mut csv = Vec::new();
+    report.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    // p50 of `my_metric` is estimated from the bucket holding 25ms
+    assert_eq!(
+        csv,
+        "metric,struct,function,labels,count,total_seconds,mean_seconds,min_seconds,p50_seconds,p90_seconds,p99_seconds,max_seconds
+my_metric,Test,method,,4,0.12,0.03,0.015,0.024903679,0.045,0.045,0.045
+callee,,callee,caller=Test::method,4,0.02,0.005,0.005,0.005,0.005,0.005,0.005
+callee,,callee,caller=__root__,1,0.005,0.005,0.005,0.005,0.005,0.005,0.005
+"
+    );
+
+    assert_eq!(Report::read_csv(csv.as_bytes()).unwrap(), report);
+}
+
+#[test]
+fn test_read_csv() {
+    let csv = "metric,struct,function,labels,count,total_seconds,mean_seconds,min_seconds,p50_seconds,p90_seconds,p99_seconds,max_seconds\r
+\"my,\"\"metric\"\"\",,f,a=1;b=2,1,1,1,1,1,1,1,1\r
+";
+    let report = Report::read_csv(csv.as_bytes()).unwrap();
+    let second = Duration::from_secs(1);
+    assert_eq!(
+        report.rows(),
+        &[Row {
+            name: "my,\"metric\"".to_string(),
+            struct_name: None,
+            function: Some("f".to_string()),
+            labels: vec![
+                ("a".to_string(), "1".to_string()),
+                ("b".to_string(), "2".to_string()),
+            ],
+            count: 1,
+            total: second,
+            mean: second,
+            min: second,
+            p50: second,
+            p90: second,
+            p99: second,
+            max: second,
+        }]
+    );
+
+    let mut written = Vec::new();
+    report.write_csv(&mut written).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), csv.replace('\r', ""));
+}
+
+#[test]
+fn test_read_invalid_csv() {
+    let error = Report::read_csv("metric,count\n".as_bytes()).unwrap_err();
+    assert_eq!(error.to_string(), "line 1: missing header");
+
+    let csv = "metric,struct,function,labels,count,total_seconds,mean_seconds,min_seconds,p50_seconds,p90_seconds,p99_seconds,max_seconds
+my_metric,,f,,1,fast,1,1,1,1,1,1
+";
+    let error = Report::read_csv(csv.as_bytes()).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "line 2: invalid total_seconds `fast`: invalid float literal"
+    );
+}
@@ -0,0 +1,27 @@
+use function_timer::report::REPORT_FILE_ENV;
+use function_timer::time;
+
+#[time("my_metric")]
+fn batch_job() {}
+
+#[test]
+fn test_report_on_exit() {
+    let path =
+        std::env::temp_dir().join(format!("function_timer_report_{}.txt", std::process::id()));
+    std::env::set_var(REPORT_FILE_ENV, &path);
+
+    let guard = function_timer::install_report_on_exit().unwrap();
+    batch_job();
+    batch_job();
+    assert!(function_timer::install_report_on_exit().is_err());
+    assert_eq!(guard.recorder().report().rows()[0].count, 2);
+    drop(guard);
+
+    let report = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let lines: Vec<&str> = report.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("metric"));
+    let cells: Vec<&str> = lines[1].split_whitespace().collect();
+    assert_eq!(cells[..3], ["my_metric", "batch_job", "2"]);
+}
@@ -0,0 +1,80 @@
+//! With `tracing`, slow calls are reported as `tracing` events instead.
+#![cfg(not(feature = "tracing"))]
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use function_timer::testing::{self, MockClock};
+use function_timer::time;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+#[derive(Default)]
+struct CaptureLogger {
+    records: Mutex<Vec<(Level, String)>>,
+}
+
+impl Log for CaptureLogger {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        self.records
+            .lock()
+            .unwrap()
+            .push((record.level(), record.args().to_string()));
+    }
+
+    fn flush(&self) {}
+}
+
+struct Test {}
+
+#[time("my_metric", slow = "10ms")]
+impl Test {
+    pub fn slow_function(&self) {
+        testing::advance(Duration::from_millis(20));
+    }
+
+    pub fn fast_function(&self) {
+        testing::advance(Duration::from_millis(5));
+    }
+}
+
+#[time("my_metric", slow = "10ms")]
+fn free_function() {
+    testing::advance(Duration::from_millis(20));
+}
+
+#[test]
+fn test_slow_log() {
+    static LOGGER: CaptureLogger = CaptureLogger {
+        records: Mutex::new(Vec::new()),
+    };
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
+    log::set_logger(&LOGGER).unwrap();
+    log::set_max_level(LevelFilter::Warn);
+
+    let test = Test {};
+    test.slow_function();
+    test.fast_function();
+    free_function();
+
+    let records = LOGGER.records.lock().unwrap();
+    assert_eq!(
+        *records,
+        [
+            (
+                Level::Warn,
+                "Slow call : metric=my_metric, struct=Test, function=slow_function, elapsed=20ms, threshold=10ms"
+                    .to_string()
+            ),
+            (
+                Level::Warn,
+                "Slow call : metric=my_metric, function=free_function, elapsed=20ms, threshold=10ms"
+                    .to_string()
+            )
+        ]
+    );
+}
@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use function_timer::testing::{self, MockClock};
+use function_timer::{assert_timed, time};
+
+struct Test {}
+
+#[time("my_metric", count = true, slow = "1s", slow_counter = true)]
+impl Test {
+    pub fn test(&self, millis: u64) {
+        testing::advance(Duration::from_millis(millis));
+    }
+}
+
+fn samples() -> testing::Samples {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
+
+    testing::capture(|| {
+        let test = Test {};
+        test.test(500);
+        test.test(1500);
+        metrics::gauge!("ignored").set(1.0);
+    })
+}
+
+#[test]
+fn test_capture() {
+    let samples = samples();
+
+    let labels = [("struct", "Test"), ("function", "test")];
+    assert_eq!(
+        samples.histogram("my_metric", &labels),
+        Some(&[0.5, 1.5][..])
+    );
+    assert_eq!(samples.counter("my_metric_calls_total", &labels), Some(2));
+    assert_eq!(samples.counter("my_metric_slow_total", &labels), Some(1));
+    assert_eq!(samples.histogram("my_metric", &labels[..1]), None);
+
+    let histograms: Vec<_> = samples.histograms().collect();
+    let sorted_labels = [
+        ("function".to_string(), "test".to_string()),
+        ("struct".to_string(), "Test".to_string()),
+    ];
+    assert_eq!(
+        histograms,
+        [("my_metric", &sorted_labels[..], &[0.5, 1.5][..])]
+    );
+    assert_eq!(samples.counters().count(), 2);
+}
+
+#[test]
+fn test_capture_ignores_cached_handles() {
+    let labels = [("struct", "Test"), ("function", "test")];
+    for _ in 0..2 {
+        let samples = samples();
+        assert_eq!(samples.counter("my_metric_calls_total", &labels), Some(2));
+    }
+}
+
+#[test]
+fn test_assert_timed() {
+    let samples = samples();
+
+    let values = assert_timed!(samples, "my_metric", function = "test", struct = "Test");
+    assert_eq!(values, [0.5, 1.5]);
+}
+
+#[test]
+#[should_panic(
+    expected = r#"No histogram `my_metric` with labels [("function", "other")], recorded histograms : [my_metric [("function", "test"), ("struct", "Test")]]"#
+)]
+fn test_assert_timed_missing() {
+    let samples = samples();
+
+    assert_timed!(samples, "my_metric", function = "other");
+}
@@ -0,0 +1,50 @@
+use function_timer::time;
+use metrics::Label;
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use metrics_util::MetricKind;
+
+struct Test {}
+
+#[time("my_metric", cached = true, count = true)]
+impl Test {
+    pub fn impl_function(&self) {}
+}
+
+#[test]
+fn test_cached_handles() {
+    // The histogram and the counter are cached for the whole process, like the global recorder
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    recorder.install().unwrap();
+
+    let t = Test {};
+    for _ in 0..3 {
+        t.impl_function();
+    }
+
+    let metrics = snapshotter.snapshot().into_vec();
+    assert_eq!(metrics.len(), 2);
+
+    for (key, _, _, debug_value) in metrics {
+        let (kind, key) = key.into_parts();
+        let (name, labels) = key.into_parts();
+        assert_eq!(
+            labels,
+            vec![
+                Label::new("struct", "Test"),
+                Label::new("function", "impl_function")
+            ]
+        );
+        match (kind, debug_value) {
+            (MetricKind::Histogram, DebugValue::Histogram(values)) => {
+                assert_eq!(name.as_str(), "my_metric");
+                assert_eq!(values.len(), 3);
+            }
+            (MetricKind::Counter, DebugValue::Counter(count)) => {
+                assert_eq!(name.as_str(), "my_metric_calls_total");
+                assert_eq!(count, 3);
+            }
+            _ => panic!("Unexpected metric"),
+        }
+    }
+}
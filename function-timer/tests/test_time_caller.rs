@@ -0,0 +1,85 @@
+use std::collections::BTreeMap;
+
+use function_timer::{testing, time};
+
+struct Cart {}
+
+#[time("cart")]
+impl Cart {
+    pub fn checkout(&self) {
+        save();
+    }
+
+    pub fn admin_edit(&self) {
+        audit();
+    }
+}
+
+#[time("audit")]
+fn audit() {
+    save();
+}
+
+#[time("save", caller = true)]
+fn save() {}
+
+#[time("deep_save", caller = 2)]
+fn deep_save() {}
+
+#[time("batch")]
+fn batch() {
+    audit_deep();
+}
+
+#[time("audit")]
+fn audit_deep() {
+    deep_save();
+}
+
+fn callers(f: impl FnOnce()) -> BTreeMap<(String, String), u64> {
+    testing::capture(f)
+        .histograms()
+        .filter_map(|(name, labels, values)| {
+            let (_, caller) = labels.iter().find(|(key, _)| key == "caller")?;
+            Some(((name.to_string(), caller.clone()), values.len() as u64))
+        })
+        .collect()
+}
+
+#[test]
+fn test_caller() {
+    let cart = Cart {};
+    let metrics = callers(|| {
+        cart.checkout();
+        cart.checkout();
+        cart.admin_edit();
+        save();
+    });
+
+    let expected: BTreeMap<(String, String), u64> = [
+        ("save", "Cart::checkout", 2),
+        ("save", "audit", 1),
+        ("save", function_timer::ROOT_CALLER, 1),
+    ]
+    .into_iter()
+    .map(|(name, caller, count)| ((name.to_string(), caller.to_string()), count))
+    .collect();
+    assert_eq!(metrics, expected);
+}
+
+#[test]
+fn test_caller_depth() {
+    let metrics = callers(|| {
+        batch();
+        deep_save();
+    });
+
+    let expected: BTreeMap<(String, String), u64> = [
+        ("deep_save", "batch/audit_deep", 1),
+        ("deep_save", function_timer::ROOT_CALLER, 1),
+    ]
+    .into_iter()
+    .map(|(name, caller, count)| ((name.to_string(), caller.to_string()), count))
+    .collect();
+    assert_eq!(metrics, expected);
+}
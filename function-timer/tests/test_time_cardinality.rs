@@ -0,0 +1,45 @@
+use function_timer::{assert_timed, testing, time};
+
+struct Test {}
+
+#[time("my_metric")]
+impl Test {
+    pub fn first(&self) {}
+
+    pub fn second(&self) {}
+
+    pub fn third(&self) {}
+}
+
+#[test]
+fn test_cardinality_limit() {
+    function_timer::set_cardinality_limit(Some(2));
+    assert_eq!(function_timer::cardinality_limit(), Some(2));
+
+    let samples = testing::capture(|| {
+        let t = Test {};
+        t.first();
+        t.second();
+        t.first();
+        t.third();
+    });
+
+    function_timer::set_cardinality_limit(None);
+    assert_eq!(function_timer::cardinality_limit(), None);
+
+    assert_eq!(samples.counters().count(), 1);
+    assert_eq!(
+        samples.counter(function_timer::OVERFLOW_COUNTER, &[("metric", "my_metric")]),
+        Some(1)
+    );
+
+    assert_eq!(samples.histograms().count(), 3);
+    assert_timed!(samples, "my_metric", struct = "Test", function = "first");
+    assert_timed!(samples, "my_metric", struct = "Test", function = "second");
+    assert_timed!(
+        samples,
+        "my_metric",
+        struct = function_timer::OVERFLOW_VALUE,
+        function = function_timer::OVERFLOW_VALUE
+    );
+}
@@ -0,0 +1,57 @@
+use std::time::Duration;
+
+use function_timer::{assert_timed, testing, time};
+
+struct Test {}
+
+#[time("my_metric", clock = "thread_cpu")]
+impl Test {
+    pub fn cpu_function(&self) -> u64 {
+        (0..1_000_000u64).fold(0, |acc, i| acc.wrapping_add(i * i))
+    }
+
+    #[time("my_metric", clock = "both")]
+    pub fn sleep_function(&self) {
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[test]
+fn test_thread_cpu() {
+    let samples = testing::capture(|| {
+        let t = Test {};
+        std::hint::black_box(t.cpu_function());
+    });
+
+    assert_eq!(samples.histograms().count(), 1);
+    if cfg!(target_os = "linux") {
+        let values = assert_timed!(
+            samples,
+            "my_metric_cpu",
+            struct = "Test",
+            function = "cpu_function"
+        );
+        assert!(values[0] > 0.0);
+    } else {
+        // Falls back to wall time
+        assert_timed!(samples, "my_metric", struct = "Test", function = "cpu_function");
+    }
+}
+
+#[test]
+fn test_wall_and_thread_cpu() {
+    let samples = testing::capture(|| {
+        let t = Test {};
+        t.sleep_function();
+    });
+
+    let wall = assert_timed!(samples, "my_metric", function = "sleep_function")[0];
+    assert!(wall >= 0.05);
+
+    if cfg!(target_os = "linux") {
+        assert_eq!(samples.histograms().count(), 2);
+        let cpu = assert_timed!(samples, "my_metric_cpu", function = "sleep_function")[0];
+        // Sleeping doesn't use CPU
+        assert!(cpu < wall);
+    }
+}
@@ -1,5 +1,6 @@
 use std::time::Duration;
 
+use function_timer::testing::{self, MockClock};
 use function_timer::time;
 use metrics::Label;
 use metrics_util::debugging::DebugValue;
@@ -10,17 +11,19 @@ struct Test {}
 #[time("my_metric")]
 impl Test {
     pub fn test(&self) {
-        std::thread::sleep(Duration::from_secs(2));
+        testing::advance(Duration::from_secs(2));
     }
 
     #[time(disable)]
     pub fn disable(&self) {
-        std::thread::sleep(Duration::from_secs(2));
+        testing::advance(Duration::from_secs(2));
     }
 }
 
 #[test]
 fn test_time_disable() {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
     let recorder = metrics_util::debugging::DebuggingRecorder::new();
 
     metrics::with_local_recorder(&recorder, || {
@@ -41,6 +44,11 @@ fn test_time_disable() {
             labels,
             vec![Label::new("struct", "Test"), Label::new("function", "test")]
         );
-        assert!(matches!(debug_value, DebugValue::Histogram(_)));
+        if let DebugValue::Histogram(values) = debug_value {
+            let values: Vec<f64> = values.iter().map(|value| value.into_inner()).collect();
+            assert_eq!(values, vec![2.0]);
+        } else {
+            panic!("Expected an histogram");
+        }
     }
 }
@@ -9,3 +9,22 @@ fn test_fail_disable_impl() {
     let t = trybuild::TestCases::new();
     t.compile_fail("tests/fail/fail_disable_struct.rs");
 }
+
+#[test]
+fn test_fail_options() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/fail/fail_options.rs");
+}
+
+#[test]
+fn test_fail_template() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/fail/fail_template.rs");
+}
+
+#[cfg(not(any(feature = "log", feature = "tracing")))]
+#[test]
+fn test_fail_slow_without_backend() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/fail/fail_slow.rs");
+}
@@ -0,0 +1,65 @@
+use std::sync::Mutex;
+
+use function_timer::{assert_timed, testing, time, LabelKeys};
+
+/// Label keys are global, tests must not run concurrently.
+static LOCK: Mutex<()> = Mutex::new(());
+
+struct Test {}
+
+#[time("my_metric", struct_label = "type", function_label = "method")]
+impl Test {
+    pub fn impl_function(&self) {}
+
+    #[time("my_metric", label_keys = "opentelemetry")]
+    pub fn otel_function(&self) {}
+}
+
+#[time("my_metric")]
+pub fn free_function() {}
+
+#[test]
+fn test_attribute_label_keys() {
+    let _lock = LOCK.lock().unwrap();
+
+    let samples = testing::capture(|| {
+        let t = Test {};
+        t.impl_function();
+    });
+    assert_eq!(samples.histograms().count(), 1);
+    assert_timed!(samples, "my_metric", type = "Test", method = "impl_function");
+
+    let samples = testing::capture(|| {
+        let t = Test {};
+        t.otel_function();
+    });
+    assert_eq!(samples.histograms().count(), 1);
+    assert!(samples
+        .histogram("my_metric", &[("code.function", "otel_function")])
+        .is_some());
+}
+
+#[test]
+fn test_global_label_keys() {
+    let _lock = LOCK.lock().unwrap();
+
+    function_timer::set_label_keys(LabelKeys::OPEN_TELEMETRY);
+    assert_eq!(function_timer::label_keys(), LabelKeys::OPEN_TELEMETRY);
+    let samples = testing::capture(free_function);
+    let overridden = testing::capture(|| {
+        let t = Test {};
+        t.impl_function();
+    });
+    function_timer::set_label_keys(LabelKeys::DEFAULT);
+
+    assert_eq!(samples.histograms().count(), 1);
+    assert!(samples
+        .histogram("my_metric", &[("code.function", "free_function")])
+        .is_some());
+    assert_eq!(overridden.histograms().count(), 1);
+    assert_timed!(overridden, "my_metric", type = "Test", method = "impl_function");
+
+    let samples = testing::capture(free_function);
+    assert_eq!(samples.histograms().count(), 1);
+    assert_timed!(samples, "my_metric", function = "free_function");
+}
@@ -0,0 +1,98 @@
+use std::sync::Mutex;
+
+use function_timer::{assert_timed, testing, time};
+
+/// Prefix is global, tests must not run concurrently.
+static LOCK: Mutex<()> = Mutex::new(());
+
+struct Test {}
+
+#[time("query_seconds", namespace = "db", count = true)]
+impl Test {
+    pub fn impl_function(&self) {}
+}
+
+#[time("my_metric")]
+pub fn free_function() {}
+
+const METRIC_NAME: &str = "ident_metric";
+
+#[time(METRIC_NAME, namespace = "db")]
+pub fn ident_function() {}
+
+#[time("{module}_{function}", namespace = "db")]
+pub fn template_function() {}
+
+#[test]
+fn test_namespace() {
+    let _lock = LOCK.lock().unwrap();
+
+    let samples = testing::capture(|| {
+        let t = Test {};
+        t.impl_function();
+    });
+
+    assert_eq!(samples.histograms().count(), 1);
+    assert_timed!(
+        samples,
+        "db_query_seconds",
+        struct = "Test",
+        function = "impl_function"
+    );
+    assert_eq!(samples.counters().count(), 1);
+    assert_eq!(
+        samples.counter(
+            "db_query_seconds_calls_total",
+            &[("struct", "Test"), ("function", "impl_function")]
+        ),
+        Some(1)
+    );
+}
+
+#[test]
+fn test_namespace_ident_and_template() {
+    let _lock = LOCK.lock().unwrap();
+
+    let samples = testing::capture(|| {
+        ident_function();
+        template_function();
+    });
+
+    assert_eq!(samples.histograms().count(), 2);
+    assert_timed!(samples, "db_ident_metric", function = "ident_function");
+    assert_timed!(
+        samples,
+        "db_test_time_name_template_function",
+        function = "template_function"
+    );
+}
+
+#[test]
+fn test_prefix() {
+    let _lock = LOCK.lock().unwrap();
+
+    function_timer::set_prefix("billing_");
+    assert_eq!(function_timer::prefix(), "billing_");
+    let samples = testing::capture(|| {
+        free_function();
+        let t = Test {};
+        t.impl_function();
+    });
+    function_timer::set_prefix("");
+    assert_eq!(function_timer::prefix(), "");
+
+    let mut names: Vec<&str> = samples
+        .histograms()
+        .map(|(name, _, _)| name)
+        .chain(samples.counters().map(|(name, _, _)| name))
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        vec![
+            "billing_db_query_seconds",
+            "billing_db_query_seconds_calls_total",
+            "billing_my_metric"
+        ]
+    );
+}
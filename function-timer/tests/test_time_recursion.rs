@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use function_timer::testing::{self, MockClock};
+use function_timer::{assert_timed, time};
+
+#[time("all")]
+fn all(n: u32) {
+    testing::advance(Duration::from_secs(1));
+    if n > 0 {
+        all(n - 1);
+    }
+}
+
+#[time("outermost", recursion = "outermost", recursion_depth = true)]
+fn outermost(n: u32) {
+    testing::advance(Duration::from_secs(1));
+    if n > 0 {
+        outermost(n - 1);
+    }
+}
+
+#[test]
+fn test_all_calls() {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
+    let samples = testing::capture(|| all(2));
+
+    assert_eq!(samples.histograms().count(), 1);
+    let values = assert_timed!(samples, "all", function = "all");
+    assert_eq!(values, [1.0, 2.0, 3.0]);
+}
+
+#[test]
+fn test_outermost_call() {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
+    let samples = testing::capture(|| {
+        outermost(2);
+        outermost(0);
+    });
+
+    assert_eq!(samples.histograms().count(), 2);
+    let values = assert_timed!(samples, "outermost", function = "outermost");
+    assert_eq!(values, [3.0, 1.0]);
+    let depths = assert_timed!(samples, "outermost_recursion_depth", function = "outermost");
+    assert_eq!(depths, [3.0, 1.0]);
+}
@@ -0,0 +1,53 @@
+use function_timer::{assert_timed, testing, time};
+
+struct Test {}
+
+#[time("my_metric", every = 3, count = true)]
+impl Test {
+    pub fn every_function(&self) {}
+
+    #[time("my_metric", sample = 0.5)]
+    pub fn sample_function(&self) {}
+}
+
+#[test]
+fn test_every() {
+    let samples = testing::capture(|| {
+        let t = Test {};
+        for _ in 0..9 {
+            t.every_function();
+        }
+    });
+
+    assert_eq!(samples.histograms().count(), 1);
+    assert_eq!(samples.counters().count(), 1);
+    let values = assert_timed!(
+        samples,
+        "my_metric",
+        struct = "Test",
+        function = "every_function"
+    );
+    assert_eq!(values.len(), 3);
+    assert_eq!(
+        samples.counter(
+            "my_metric_calls_total",
+            &[("struct", "Test"), ("function", "every_function")]
+        ),
+        Some(9)
+    );
+}
+
+#[test]
+fn test_sample() {
+    let samples = testing::capture(|| {
+        let t = Test {};
+        for _ in 0..1000 {
+            t.sample_function();
+        }
+    });
+
+    assert_eq!(samples.histograms().count(), 1);
+    assert_eq!(samples.counters().count(), 0);
+    let values = assert_timed!(samples, "my_metric", function = "sample_function");
+    assert!(values.len() > 300 && values.len() < 700);
+}
@@ -0,0 +1,48 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use function_timer::testing::{self, MockClock};
+use function_timer::time;
+
+#[time("parent", self_time = true)]
+fn parent() {
+    testing::advance(Duration::from_secs(1));
+    child();
+    child();
+}
+
+#[time("child", self_time = true)]
+fn child() {
+    testing::advance(Duration::from_secs(1));
+    grand_child();
+}
+
+#[time("grand_child")]
+fn grand_child() {
+    testing::advance(Duration::from_millis(500));
+}
+
+#[test]
+fn test_self_time() {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
+
+    let samples = testing::capture(parent);
+
+    let metrics: BTreeMap<String, Vec<f64>> = samples
+        .histograms()
+        .map(|(name, _, values)| (name.to_string(), values.to_vec()))
+        .collect();
+
+    let expected: BTreeMap<String, Vec<f64>> = [
+        ("parent", vec![4.0]),
+        ("parent_self_seconds", vec![1.0]),
+        ("child", vec![1.5, 1.5]),
+        ("child_self_seconds", vec![1.0, 1.0]),
+        ("grand_child", vec![0.5, 0.5]),
+    ]
+    .into_iter()
+    .map(|(name, values)| (name.to_string(), values))
+    .collect();
+    assert_eq!(metrics, expected);
+}
@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use function_timer::{assert_timed, testing, time};
+
+struct Test {}
+
+#[time("my_metric", slo = ["1ms", "10ms", "1h"])]
+impl Test {
+    pub fn impl_function(&self) {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[time("my_metric", slo = "1h")]
+pub fn free_function() {}
+
+#[test]
+fn test_slo_violations() {
+    let samples = testing::capture(|| {
+        let t = Test {};
+        t.impl_function();
+    });
+
+    assert_eq!(samples.counters().count(), 2);
+    for threshold in ["1ms", "10ms"] {
+        assert_eq!(
+            samples.counter(
+                "my_metric_slo_violations_total",
+                &[
+                    ("struct", "Test"),
+                    ("function", "impl_function"),
+                    ("threshold", threshold),
+                ]
+            ),
+            Some(1)
+        );
+    }
+}
+
+#[test]
+fn test_slo_respected() {
+    let samples = testing::capture(free_function);
+
+    assert_eq!(samples.counters().count(), 0);
+    assert_eq!(samples.histograms().count(), 1);
+    assert_timed!(samples, "my_metric", function = "free_function");
+}
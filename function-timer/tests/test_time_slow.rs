@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use function_timer::time;
+use metrics::Label;
+use metrics_util::debugging::DebugValue;
+use metrics_util::MetricKind;
+
+struct Test {}
+
+#[time("my_metric", slow = "1ms", slow_counter = true)]
+impl Test {
+    pub fn slow_function(&self) {
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    #[time("my_metric", slow = "1h", slow_counter = true)]
+    pub fn fast_function(&self) {}
+}
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+#[time("my_metric", slow = "1ms")]
+pub fn free_function() {
+    std::thread::sleep(Duration::from_millis(20));
+}
+
+#[test]
+fn test_slow_counter() {
+    let recorder = metrics_util::debugging::DebuggingRecorder::new();
+
+    metrics::with_local_recorder(&recorder, || {
+        let t = Test {};
+        t.slow_function();
+    });
+
+    let metrics = recorder.snapshotter().snapshot().into_vec();
+    assert_eq!(metrics.len(), 2);
+
+    for (key, _, _, debug_value) in metrics {
+        let (kind, key) = key.into_parts();
+        let (name, labels) = key.into_parts();
+        assert_eq!(
+            labels,
+            vec![
+                Label::new("struct", "Test"),
+                Label::new("function", "slow_function")
+            ]
+        );
+        match kind {
+            MetricKind::Histogram => {
+                assert_eq!(name.as_str(), "my_metric");
+                assert!(matches!(debug_value, DebugValue::Histogram(_)));
+            }
+            MetricKind::Counter => {
+                assert_eq!(name.as_str(), "my_metric_slow_total");
+                assert_eq!(debug_value, DebugValue::Counter(1));
+            }
+            MetricKind::Gauge => panic!("Unexpected gauge"),
+        }
+    }
+}
+
+#[test]
+fn test_not_slow() {
+    let recorder = metrics_util::debugging::DebuggingRecorder::new();
+
+    metrics::with_local_recorder(&recorder, || {
+        let t = Test {};
+        t.fast_function();
+    });
+
+    let metrics = recorder.snapshotter().snapshot().into_vec();
+    assert_eq!(metrics.len(), 1);
+
+    for (key, _, _, debug_value) in metrics {
+        let (kind, key) = key.into_parts();
+        let (name, _) = key.into_parts();
+        assert_eq!(kind, MetricKind::Histogram);
+        assert_eq!(name.as_str(), "my_metric");
+        assert!(matches!(debug_value, DebugValue::Histogram(_)));
+    }
+}
+
+#[cfg(any(feature = "log", feature = "tracing"))]
+#[test]
+fn test_slow_without_counter() {
+    let recorder = metrics_util::debugging::DebuggingRecorder::new();
+
+    metrics::with_local_recorder(&recorder, || {
+        free_function();
+    });
+
+    let metrics = recorder.snapshotter().snapshot().into_vec();
+    assert_eq!(metrics.len(), 1);
+
+    for (key, _, _, debug_value) in metrics {
+        let (kind, key) = key.into_parts();
+        let (name, labels) = key.into_parts();
+        assert_eq!(kind, MetricKind::Histogram);
+        assert_eq!(name.as_str(), "my_metric");
+        assert_eq!(labels, vec![Label::new("function", "free_function")]);
+        assert!(matches!(debug_value, DebugValue::Histogram(_)));
+    }
+}
@@ -1,6 +1,7 @@
 use std::error::Error;
 use std::time::Duration;
 
+use function_timer::testing::{self, MockClock};
 use function_timer::time;
 use metrics::Label;
 use metrics_util::debugging::DebugValue;
@@ -11,16 +12,16 @@ struct Test {}
 #[time("my_metric")]
 impl Test {
     pub fn impl_function(&self) {
-        std::thread::sleep(Duration::from_secs(2));
+        testing::advance(Duration::from_secs(2));
     }
 
     #[time("other_metric")]
     pub fn static_function() {
-        std::thread::sleep(Duration::from_secs(2));
+        testing::advance(Duration::from_secs(2));
     }
 
     pub fn impl_fail_function(&self, text: &str) -> Result<(), Box<dyn Error>> {
-        std::thread::sleep(Duration::from_secs(2));
+        testing::advance(Duration::from_secs(2));
         let number: usize = text.parse()?;
         println!("{number}");
 
@@ -45,6 +46,8 @@ impl MyTrait for Test {
 
 #[test]
 fn test_time_static_function() {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
     let recorder = metrics_util::debugging::DebuggingRecorder::new();
 
     metrics::with_local_recorder(&recorder, || {
@@ -65,6 +68,8 @@ fn test_time_static_function() {
 
 #[test]
 fn test_time_impl_function() {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
     let recorder = metrics_util::debugging::DebuggingRecorder::new();
 
     metrics::with_local_recorder(&recorder, || {
@@ -92,6 +97,8 @@ fn test_time_impl_function() {
 
 #[test]
 fn test_time_impl_fail_function() {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
     let recorder = metrics_util::debugging::DebuggingRecorder::new();
 
     metrics::with_local_recorder(&recorder, || {
@@ -119,6 +126,8 @@ fn test_time_impl_fail_function() {
 
 #[test]
 fn test_time_impl_trait() {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
     let recorder = metrics_util::debugging::DebuggingRecorder::new();
 
     metrics::with_local_recorder(&recorder, || {
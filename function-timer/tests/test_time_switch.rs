@@ -0,0 +1,60 @@
+use std::sync::Mutex;
+
+use function_timer::testing::{self, Samples};
+use function_timer::{assert_timed, time};
+
+/// Switches are global, tests must not run concurrently.
+static LOCK: Mutex<()> = Mutex::new(());
+
+#[time("my_metric", count = true)]
+pub fn my_function() {}
+
+#[time("other_metric")]
+pub fn other_function() {}
+
+#[test]
+fn test_global_switch() {
+    let _lock = LOCK.lock().unwrap();
+
+    function_timer::set_enabled(false);
+    assert!(!function_timer::is_enabled());
+    let samples = testing::capture(|| {
+        my_function();
+        other_function();
+    });
+    function_timer::set_enabled(true);
+
+    assert_eq!(samples, Samples::default());
+
+    let samples = testing::capture(my_function);
+    assert_timed!(samples, "my_metric", function = "my_function");
+    assert_eq!(
+        samples.counter("my_metric_calls_total", &[("function", "my_function")]),
+        Some(1)
+    );
+}
+
+#[test]
+fn test_metric_switch() {
+    let _lock = LOCK.lock().unwrap();
+
+    function_timer::set_metric_enabled("my_metric", false);
+    assert!(!function_timer::is_metric_enabled("my_metric"));
+    assert!(function_timer::is_metric_enabled("other_metric"));
+    let samples = testing::capture(|| {
+        my_function();
+        other_function();
+    });
+    function_timer::set_metric_enabled("my_metric", true);
+
+    assert_eq!(samples.histograms().count(), 1);
+    assert_eq!(samples.counters().count(), 0);
+    assert_timed!(samples, "other_metric", function = "other_function");
+
+    let samples = testing::capture(my_function);
+    assert_timed!(samples, "my_metric", function = "my_function");
+    assert_eq!(
+        samples.counter("my_metric_calls_total", &[("function", "my_function")]),
+        Some(1)
+    );
+}
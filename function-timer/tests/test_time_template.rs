@@ -0,0 +1,85 @@
+use function_timer::{assert_timed, testing, time};
+
+struct Test {}
+
+#[time("{struct}_{function}_seconds")]
+impl Test {
+    pub fn impl_function(&self) {}
+}
+
+trait MyTrait {
+    fn trait_function(&self);
+}
+
+#[time("{trait}_{struct}_{function}")]
+impl MyTrait for Test {
+    fn trait_function(&self) {}
+}
+
+#[time("{module}_{function}")]
+pub fn free_function() {}
+
+mod nested {
+    pub mod inner {
+        use function_timer::time;
+
+        #[time("{module}_{function}")]
+        pub fn nested_function() {}
+    }
+}
+
+#[test]
+fn test_template_struct() {
+    let samples = testing::capture(|| {
+        let t = Test {};
+        t.impl_function();
+    });
+
+    assert_eq!(samples.histograms().count(), 1);
+    assert_timed!(
+        samples,
+        "Test_impl_function_seconds",
+        struct = "Test",
+        function = "impl_function"
+    );
+}
+
+#[test]
+fn test_template_trait() {
+    let samples = testing::capture(|| {
+        let t = Test {};
+        t.trait_function();
+    });
+
+    assert_eq!(samples.histograms().count(), 1);
+    assert_timed!(
+        samples,
+        "MyTrait_Test_trait_function",
+        struct = "Test",
+        function = "trait_function"
+    );
+}
+
+#[test]
+fn test_template_module() {
+    let samples = testing::capture(free_function);
+
+    assert_eq!(samples.histograms().count(), 1);
+    assert_timed!(
+        samples,
+        "test_time_template_free_function",
+        function = "free_function"
+    );
+}
+
+#[test]
+fn test_template_nested_module() {
+    let samples = testing::capture(nested::inner::nested_function);
+
+    assert_eq!(samples.histograms().count(), 1);
+    assert_timed!(
+        samples,
+        "test_time_template_nested_inner_nested_function",
+        function = "nested_function"
+    );
+}
@@ -0,0 +1,67 @@
+use function_timer::{assert_timed, testing, time};
+
+struct Test {
+    threshold: usize,
+}
+
+impl Test {
+    #[time("my_metric", when = batch.len() > self.threshold)]
+    pub fn impl_function(&self, batch: &[u8]) -> usize {
+        batch.len()
+    }
+}
+
+#[time("my_metric", when = size > 1000)]
+pub async fn async_function(size: usize) -> usize {
+    size
+}
+
+#[derive(PartialEq)]
+pub struct Point {
+    x: i32,
+}
+
+#[time("my_metric", when = point == Point { x: 1 })]
+pub fn struct_literal_function(point: Point) -> i32 {
+    point.x
+}
+
+#[test]
+fn test_when() {
+    let samples = testing::capture(|| {
+        let t = Test { threshold: 2 };
+        assert_eq!(t.impl_function(&[1, 2]), 2);
+        assert_eq!(t.impl_function(&[1, 2, 3]), 3);
+        assert_eq!(t.impl_function(&[1, 2, 3, 4]), 4);
+    });
+
+    assert_eq!(samples.histograms().count(), 1);
+    assert_eq!(samples.counters().count(), 0);
+    let values = assert_timed!(samples, "my_metric", function = "impl_function");
+    assert_eq!(values.len(), 2);
+}
+
+#[test]
+fn test_when_async() {
+    let samples = testing::capture(|| {
+        assert_eq!(futures_executor::block_on(async_function(10)), 10);
+        assert_eq!(futures_executor::block_on(async_function(2000)), 2000);
+    });
+
+    assert_eq!(samples.histograms().count(), 1);
+    assert_eq!(samples.counters().count(), 0);
+    let values = assert_timed!(samples, "my_metric", function = "async_function");
+    assert_eq!(values.len(), 1);
+}
+
+#[test]
+fn test_when_struct_literal() {
+    let samples = testing::capture(|| {
+        assert_eq!(struct_literal_function(Point { x: 0 }), 0);
+        assert_eq!(struct_literal_function(Point { x: 1 }), 1);
+    });
+
+    assert_eq!(samples.histograms().count(), 1);
+    let values = assert_timed!(samples, "my_metric", function = "struct_literal_function");
+    assert_eq!(values.len(), 1);
+}
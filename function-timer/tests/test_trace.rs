@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+use function_timer::testing::{self, MockClock};
+use function_timer::time;
+use function_timer::trace::{self, TraceEvent};
+
+struct Test {}
+
+#[time("my_metric")]
+impl Test {
+    pub fn outer(&self) {
+        testing::advance(Duration::from_millis(1));
+        inner();
+        testing::advance(Duration::from_millis(1));
+    }
+}
+
+#[time("inner_metric")]
+fn inner() {
+    testing::advance(Duration::from_micros(1500));
+}
+
+#[test]
+fn test_trace() {
+    inner();
+
+    let trace = std::thread::Builder::new()
+        .name("worker".to_string())
+        .spawn(|| {
+            static CLOCK: MockClock = MockClock::new();
+            let _clock = CLOCK.install();
+            CLOCK.set(Duration::from_secs(10));
+
+            trace::start();
+            Test {}.outer();
+            let trace = trace::stop();
+            inner();
+            trace
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let events = trace.events();
+    assert_eq!(events.len(), 2);
+    let thread_id = events[0].thread_id;
+    assert_eq!(
+        events[0],
+        TraceEvent {
+            name: "inner".to_string(),
+            metric: "inner_metric".to_string(),
+            begin: Duration::from_millis(10_001),
+            duration: Duration::from_micros(1500),
+            thread_id,
+            thread_name: Some("worker".to_string()),
+            labels: vec![("function".to_string(), "inner".to_string())],
+        }
+    );
+    assert_eq!(
+        events[1],
+        TraceEvent {
+            name: "Test::outer".to_string(),
+            metric: "my_metric".to_string(),
+            begin: Duration::from_secs(10),
+            duration: Duration::from_micros(3500),
+            thread_id,
+            thread_name: Some("worker".to_string()),
+            labels: vec![
+                ("struct".to_string(), "Test".to_string()),
+                ("function".to_string(), "outer".to_string()),
+            ],
+        }
+    );
+
+    let pid = std::process::id();
+    let expected = format!(
+        r#"[{{"name":"thread_name","ph":"M","pid":{pid},"tid":{thread_id},"args":{{"name":"worker"}}}},
+{{"name":"inner","cat":"inner_metric","ph":"X","ts":10001000.000,"dur":1500.000,"pid":{pid},"tid":{thread_id},"args":{{"function":"inner"}}}},
+{{"name":"Test::outer","cat":"my_metric","ph":"X","ts":10000000.000,"dur":3500.000,"pid":{pid},"tid":{thread_id},"args":{{"struct":"Test","function":"outer"}}}}]"#
+    );
+    assert_eq!(trace.to_json(), expected);
+
+    let mut written = Vec::new();
+    trace.write_json(&mut written).unwrap();
+    assert_eq!(String::from_utf8(written).unwrap(), expected);
+}
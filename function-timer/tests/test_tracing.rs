@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use function_timer::testing::{self, MockClock};
+use function_timer::time;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+#[derive(Debug, Default, Clone)]
+struct CapturedSpan {
+    name: &'static str,
+    fields: HashMap<String, String>,
+    entered: bool,
+}
+
+impl Visit for CapturedSpan {
+    fn record_f64(&mut self, field: &Field, value: f64) {
+        self.fields
+            .insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.fields
+            .insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        self.fields
+            .insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
+
+#[derive(Default, Clone)]
+struct CaptureSubscriber {
+    next_id: Arc<AtomicU64>,
+    spans: Arc<Mutex<HashMap<u64, CapturedSpan>>>,
+    events: Arc<Mutex<Vec<HashMap<String, String>>>>,
+}
+
+impl Subscriber for CaptureSubscriber {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut captured = CapturedSpan {
+            name: span.metadata().name(),
+            ..Default::default()
+        };
+        span.record(&mut captured);
+        self.spans.lock().unwrap().insert(id, captured);
+        Id::from_u64(id)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        if let Some(captured) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            values.record(captured);
+        }
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut captured = CapturedSpan::default();
+        event.record(&mut captured);
+        self.events.lock().unwrap().push(captured.fields);
+    }
+
+    fn enter(&self, span: &Id) {
+        if let Some(captured) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            captured.entered = true;
+        }
+    }
+
+    fn exit(&self, _: &Id) {}
+}
+
+struct Test {}
+
+#[time("my_metric")]
+impl Test {
+    pub fn impl_function(&self) {}
+
+    pub async fn async_function(&self) {}
+}
+
+#[time("my_metric")]
+pub fn free_function() {}
+
+#[time("my_metric", slow = "10ms")]
+impl Test {
+    pub fn slow_function(&self) {
+        testing::advance(Duration::from_millis(20));
+    }
+}
+
+fn capture(f: impl FnOnce()) -> Vec<CapturedSpan> {
+    let subscriber = CaptureSubscriber::default();
+
+    let samples = tracing::subscriber::with_default(subscriber.clone(), || testing::capture(f));
+
+    assert_eq!(samples.counters().count(), 0);
+    let names: Vec<&str> = samples.histograms().map(|(name, _, _)| name).collect();
+    assert_eq!(names, ["my_metric"]);
+
+    let spans = subscriber.spans.lock().unwrap();
+    spans.values().cloned().collect()
+}
+
+#[test]
+fn test_span_free_function() {
+    let spans = capture(free_function);
+
+    assert_eq!(spans.len(), 1);
+    let span = &spans[0];
+    assert_eq!(span.name, "free_function");
+    assert_eq!(span.fields.get("function").unwrap(), "free_function");
+    assert!(!span.fields.contains_key("struct"));
+    assert!(span.fields.get("elapsed").unwrap().parse::<f64>().is_ok());
+    assert!(span.entered);
+}
+
+#[test]
+fn test_span_impl_function() {
+    let spans = capture(|| {
+        let t = Test {};
+        t.impl_function();
+    });
+
+    assert_eq!(spans.len(), 1);
+    let span = &spans[0];
+    assert_eq!(span.name, "impl_function");
+    assert_eq!(span.fields.get("struct").unwrap(), "Test");
+    assert_eq!(span.fields.get("function").unwrap(), "impl_function");
+    assert!(span.fields.get("elapsed").unwrap().parse::<f64>().is_ok());
+    assert!(span.entered);
+}
+
+#[test]
+fn test_span_async_function() {
+    let spans = capture(|| {
+        let t = Test {};
+        futures_executor::block_on(t.async_function());
+    });
+
+    assert_eq!(spans.len(), 1);
+    let span = &spans[0];
+    assert_eq!(span.name, "async_function");
+    assert_eq!(span.fields.get("struct").unwrap(), "Test");
+    assert!(span.fields.get("elapsed").unwrap().parse::<f64>().is_ok());
+    assert!(span.entered);
+}
+
+#[test]
+fn test_slow_event() {
+    static CLOCK: MockClock = MockClock::new();
+    let _clock = CLOCK.install();
+    let subscriber = CaptureSubscriber::default();
+
+    tracing::subscriber::with_default(subscriber.clone(), || Test {}.slow_function());
+
+    let events = subscriber.events.lock().unwrap();
+    assert_eq!(events.len(), 1);
+    let fields: Vec<(&str, &str)> = [
+        "message",
+        "metric",
+        "struct",
+        "function",
+        "elapsed",
+        "threshold",
+    ]
+    .into_iter()
+    .map(|field| (field, events[0].get(field).map_or("", String::as_str)))
+    .collect();
+    assert_eq!(
+        fields,
+        [
+            ("message", "Slow call"),
+            ("metric", "my_metric"),
+            ("struct", "Test"),
+            ("function", "slow_function"),
+            ("elapsed", "20ms"),
+            ("threshold", "10ms"),
+        ]
+    );
+}